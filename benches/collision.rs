@@ -0,0 +1,124 @@
+#![feature(test)]
+
+extern crate stars_rs;
+extern crate test;
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+use stars_rs::{
+    spatial_hash::SpatialHash,
+    utils::{random_in_range, Collider},
+};
+use test::{black_box, Bencher};
+
+/// Side of the square the entities are spread over, wide enough for thousands of
+/// enemies to not all overlap.
+static FIELD_SIZE: f32 = 4000.0;
+/// Hit area of a drifter.
+static ENEMY_HALF_SIZE: f32 = 32.0;
+static BULLET_RADIUS: f32 = 2.5;
+
+struct Enemy;
+
+fn positions(count: usize, seed: u64) -> Vec<Vec2> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let half = FIELD_SIZE / 2.0;
+
+    (0..count)
+        .map(|_| {
+            Vec2::new(
+                random_in_range(&mut rng, -half, half),
+                random_in_range(&mut rng, -half, half),
+            )
+        })
+        .collect()
+}
+
+fn enemy(position: Vec2) -> Collider {
+    Collider::OrientedBox {
+        center: position,
+        half_size: Vec2::splat(ENEMY_HALF_SIZE),
+        // Any angle, as long as every run gets the same.
+        rotation: position.x,
+    }
+}
+
+fn bullet(position: Vec2) -> Collider {
+    Collider::Capsule {
+        start: position,
+        end: position + Vec2::Y * 15.0,
+        radius: BULLET_RADIUS,
+    }
+}
+
+/// Hits found by testing every bullet against every enemy.
+fn nested_loops(bullets: &[Vec2], enemies: &[Vec2]) -> usize {
+    bullets
+        .iter()
+        .map(|position| {
+            let collider = bullet(*position);
+            enemies
+                .iter()
+                .filter(|position| collider.intersects(&enemy(**position)))
+                .count()
+        })
+        .sum()
+}
+
+/// Hits found through the grid, rebuilding it first as every tick does.
+fn spatial_hash(grid: &mut SpatialHash<Enemy>, bullets: &[Vec2], enemies: &[Vec2]) -> usize {
+    grid.clear();
+    for (index, position) in enemies.iter().enumerate() {
+        grid.insert(Entity::from_raw(index as u32), &enemy(*position));
+    }
+
+    bullets
+        .iter()
+        .map(|position| {
+            let collider = bullet(*position);
+            grid.query(&collider)
+                .into_iter()
+                .filter(|entity| collider.intersects(&enemy(enemies[entity.index() as usize])))
+                .count()
+        })
+        .sum()
+}
+
+fn bench_nested_loops(b: &mut Bencher, count: usize) {
+    let bullets = positions(count, 1);
+    let enemies = positions(count, 2);
+
+    b.iter(|| black_box(nested_loops(&bullets, &enemies)));
+}
+
+fn bench_spatial_hash(b: &mut Bencher, count: usize) {
+    let bullets = positions(count, 1);
+    let enemies = positions(count, 2);
+    let mut grid = SpatialHash::default();
+    assert_eq!(
+        spatial_hash(&mut grid, &bullets, &enemies),
+        nested_loops(&bullets, &enemies)
+    );
+
+    b.iter(|| black_box(spatial_hash(&mut grid, &bullets, &enemies)));
+}
+
+#[bench]
+fn nested_loops_1000(b: &mut Bencher) {
+    bench_nested_loops(b, 1000);
+}
+
+#[bench]
+fn spatial_hash_1000(b: &mut Bencher) {
+    bench_spatial_hash(b, 1000);
+}
+
+#[bench]
+fn nested_loops_5000(b: &mut Bencher) {
+    bench_nested_loops(b, 5000);
+}
+
+#[bench]
+fn spatial_hash_5000(b: &mut Bencher) {
+    bench_spatial_hash(b, 5000);
+}
@@ -1,11 +1,16 @@
 use crate::{
+    character::Character,
     consts::{POSITION_Z, WINDOW_SIZE},
+    controls::ActionInput,
     state::{AppState, LoaderState},
 };
 use bevy::prelude::*;
 
+/// Distance from the character at which an aim stick puts the aim.
+static AIM_DISTANCE: f32 = 150.0;
+
 #[derive(Component)]
-struct Aim;
+pub struct Aim;
 
 fn setup(mut commands: Commands, loader: Res<LoaderState>) {
     commands
@@ -28,12 +33,46 @@ fn follow_mouse(
     }
 }
 
+/// Points the character and the aim sprite where the gamepad's right stick or the
+/// touch aim stick is pushed.
+fn follow_stick(
+    actions: ActionInput,
+    mut character_query: Query<&mut Character>,
+    mut aim_query: Query<&mut Transform, With<Aim>>,
+) {
+    let stick = actions.aim_stick();
+    if stick == Vec2::ZERO {
+        return;
+    }
+
+    for mut character in character_query.iter_mut() {
+        let target = character.position + stick.normalize() * AIM_DISTANCE;
+        character.aim_at(target);
+
+        for mut transform in aim_query.iter_mut() {
+            transform.translation.x = target.x;
+            transform.translation.y = target.y;
+        }
+    }
+}
+
 pub struct AimPlugin;
 
 impl Plugin for AimPlugin {
     fn build(&self, app: &mut App) {
         app.add_system_set(SystemSet::on_exit(AppState::Loading).with_system(setup))
-            .add_system_set(SystemSet::on_update(AppState::Menu).with_system(follow_mouse))
-            .add_system_set(SystemSet::on_update(AppState::Main).with_system(follow_mouse));
+            .add_system_set(SystemSet::on_update(AppState::Main).with_system(follow_stick));
+
+        for state in [
+            AppState::Menu,
+            AppState::Main,
+            AppState::Paused,
+            AppState::GameOver,
+            AppState::Victory,
+            AppState::HighScores,
+            AppState::Controls,
+        ] {
+            app.add_system_set(SystemSet::on_update(state).with_system(follow_mouse));
+        }
     }
 }
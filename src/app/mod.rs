@@ -1,9 +1,10 @@
+use crate::state::AppState;
 use bevy::{prelude::*, window::close_on_esc};
 
 pub struct AppPlugin;
 
 impl Plugin for AppPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(close_on_esc);
+        app.add_system_set(SystemSet::on_update(AppState::Menu).with_system(close_on_esc));
     }
 }
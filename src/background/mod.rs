@@ -49,6 +49,8 @@ impl Plugin for BackgroundPlugin {
     fn build(&self, app: &mut App) {
         app.add_system_set(SystemSet::on_exit(AppState::Loading).with_system(add_background))
             .add_system_set(SystemSet::on_enter(AppState::Menu).with_system(add_tint))
+            .add_system_set(SystemSet::on_enter(AppState::GameOver).with_system(add_tint))
+            .add_system_set(SystemSet::on_enter(AppState::Victory).with_system(add_tint))
             .add_system_set(SystemSet::on_enter(AppState::Main).with_system(remove_tint))
             .add_system_set(SystemSet::on_update(AppState::Main).with_system(move_background));
     }
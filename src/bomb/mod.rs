@@ -0,0 +1,149 @@
+use crate::{
+    character::{set_inactive, Character, CharacterActive, CharacterInactiveTimer},
+    config::GameConfig,
+    controls::{Action, ActionInput},
+    enemies::Enemy,
+    enemy_bullet::EnemyBullet,
+    events::{BombChargesEvent, BombEvent, DamageEvent},
+    health::Health,
+    state::{AppState, LoaderState},
+    timestep::{on_fixed_update, FixedUpdateLabel, FixedUpdateStage},
+    utils::GetPosition,
+};
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct BombCharges {
+    pub charges: u32,
+}
+
+fn setup_bomb_charges(mut commands: Commands, config: Res<GameConfig>) {
+    commands.spawn_empty().insert(BombCharges {
+        charges: config.bomb.charges,
+    });
+}
+
+fn reset_bomb_charges(
+    config: Res<GameConfig>,
+    mut query: Query<&mut BombCharges>,
+    mut bomb_charges_events: EventWriter<BombChargesEvent>,
+) {
+    for mut bomb_charges in query.iter_mut() {
+        bomb_charges.charges = config.bomb.charges;
+
+        bomb_charges_events.send(BombChargesEvent {
+            charges: bomb_charges.charges,
+        });
+    }
+}
+
+/// Sets off a bomb around the character on the bomb action, while charges last.
+fn use_bomb(
+    actions: ActionInput,
+    config: Res<GameConfig>,
+    mut character_query: Query<(
+        &Character,
+        &mut CharacterActive,
+        &mut CharacterInactiveTimer,
+    )>,
+    mut query: Query<&mut BombCharges>,
+    mut bomb_events: EventWriter<BombEvent>,
+    mut bomb_charges_events: EventWriter<BombChargesEvent>,
+) {
+    if !actions.just_pressed(Action::Bomb) {
+        return;
+    }
+
+    let Ok((character, mut character_active, mut inactive_timer)) =
+        character_query.get_single_mut()
+    else {
+        return;
+    };
+
+    let mut bomb_charges = query.single_mut();
+    if bomb_charges.charges == 0 {
+        return;
+    }
+    bomb_charges.charges -= 1;
+
+    bomb_events.send(BombEvent {
+        position: character.position,
+        radius: config.bomb.radius,
+    });
+    bomb_charges_events.send(BombChargesEvent {
+        charges: bomb_charges.charges,
+    });
+
+    set_inactive(
+        &mut character_active,
+        &mut inactive_timer,
+        config.bomb.invulnerability,
+    );
+}
+
+/// Kills every enemy and blows up every enemy bullet in range of a `BombEvent`, the
+/// kills are reported as fatal `DamageEvent`s. The boss shrugs bombs off.
+fn detonate(
+    mut bomb_events: EventReader<BombEvent>,
+    mut enemy_query: Query<(Entity, &Enemy, &mut Health)>,
+    enemy_bullet_query: Query<(Entity, &EnemyBullet)>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut commands: Commands,
+    loader: Res<LoaderState>,
+    audio: Res<Audio>,
+) {
+    for bomb_event in bomb_events.iter() {
+        let in_range = |position: Vec2| position.distance(bomb_event.position) <= bomb_event.radius;
+
+        for (entity, enemy, mut health) in enemy_query.iter_mut() {
+            if !health.is_alive() || !in_range(enemy.position) {
+                continue;
+            }
+
+            let damage = health.current;
+            health.kill();
+
+            damage_events.send(DamageEvent {
+                entity,
+                position: enemy.position,
+                damage,
+                fatal: true,
+            });
+        }
+
+        for (entity, enemy_bullet) in enemy_bullet_query.iter() {
+            if in_range(enemy_bullet.get_position()) {
+                commands.entity(entity).despawn();
+            }
+        }
+
+        audio.play(loader.explosion_sound.clone());
+    }
+}
+
+/// Drops the bombs read by the last tick. `BombEvent`s are sent by input every frame
+/// and read every tick, so they are kept for two ticks instead of two frames.
+fn update_bomb_events(mut bomb_events: ResMut<Events<BombEvent>>) {
+    bomb_events.update();
+}
+
+pub struct BombPlugin;
+
+impl Plugin for BombPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_bomb_charges)
+            .add_system_set(SystemSet::on_enter(AppState::Main).with_system(reset_bomb_charges))
+            .add_system_set(SystemSet::on_update(AppState::Main).with_system(use_bomb))
+            .add_system_set_to_stage(
+                FixedUpdateStage,
+                on_fixed_update(AppState::Main)
+                    .label(FixedUpdateLabel::Damage)
+                    .after(FixedUpdateLabel::Collision)
+                    .with_system(detonate),
+            )
+            .add_system_to_stage(
+                FixedUpdateStage,
+                update_bomb_events.after(FixedUpdateLabel::Damage),
+            );
+    }
+}
@@ -0,0 +1,438 @@
+use crate::utils;
+use crate::{
+    character::Character,
+    config::{BossConfig, GameConfig},
+    consts::{POSITION_Z, WINDOW_SIZE},
+    enemies::{add_enemies, EnemyAnimationTimer, EnemyCount, EnemyTextureAtlas},
+    enemy_bullet::add_enemy_bullet,
+    events::{AddExplosionEvent, DamageEvent, EnemiesLeftEvent},
+    health::{Health, HitFlash},
+    rng::GameRng,
+    state::{despawn_with, AppState, LoaderState},
+    timestep::{
+        interpolate, on_fixed_update, FixedUpdateLabel, FixedUpdateStage, PreviousPosition,
+        TIMESTEP,
+    },
+    utils::{BoundingRect, GetBoundingRect, GetPosition},
+    waves::Wave,
+};
+use bevy::{prelude::*, sprite::Anchor, utils::Duration};
+use std::f32::consts::TAU;
+
+pub static BOSS_SIZE: utils::Size = utils::Size {
+    width: 192.0,
+    height: 192.0,
+};
+pub static BOSS_SCORE: u32 = 5000;
+const BOSS_COLOR: Color = Color::CRIMSON;
+static HOVER_HEIGHT: f32 = 120.0;
+static HOVER_RANGE: f32 = 150.0;
+static HOVER_RATE: f32 = 2.0;
+/// Seconds the boss holds still before a charge.
+static CHARGE_WINDUP: f32 = 0.75;
+static BLAST_COUNT: u32 = 12;
+static BLAST_INTERVAL: f32 = 0.12;
+static HEALTH_BAR_SIZE: utils::Size = utils::Size {
+    width: 240.0,
+    height: 8.0,
+};
+
+/// Attack pattern of the boss, phases take turns in this order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BossPhase {
+    Rings,
+    Charge,
+    Minions,
+}
+
+impl BossPhase {
+    fn next(self) -> Self {
+        match self {
+            BossPhase::Rings => BossPhase::Charge,
+            BossPhase::Charge => BossPhase::Minions,
+            BossPhase::Minions => BossPhase::Rings,
+        }
+    }
+}
+
+/// End-of-run enemy. It stays around while its `Health` is zero to play its death
+/// sequence, collisions ignore it from then on.
+#[derive(Component)]
+pub struct Boss {
+    pub position: Vec2,
+    pub speed: Vec2,
+    phase: BossPhase,
+    phase_timer: Timer,
+    ring_timer: Timer,
+    charge_target: Option<Vec2>,
+    minions_due: bool,
+    blast_timer: Timer,
+    blasts: u32,
+    age: f32,
+}
+
+impl Boss {
+    fn new(config: &BossConfig, position: Vec2) -> Self {
+        let mut boss = Self {
+            position,
+            speed: Vec2::ZERO,
+            phase: BossPhase::Rings,
+            phase_timer: Timer::from_seconds(config.phase_duration, TimerMode::Once),
+            ring_timer: Timer::from_seconds(config.ring_interval, TimerMode::Once),
+            charge_target: None,
+            minions_due: false,
+            blast_timer: Timer::from_seconds(BLAST_INTERVAL, TimerMode::Repeating),
+            blasts: 0,
+            age: 0.0,
+        };
+        boss.enter_phase(BossPhase::Rings, None);
+        boss
+    }
+
+    pub fn phase(&self) -> BossPhase {
+        self.phase
+    }
+
+    fn enter_phase(&mut self, phase: BossPhase, target: Option<Vec2>) {
+        self.phase = phase;
+        self.phase_timer.reset();
+        self.charge_target = None;
+
+        match phase {
+            BossPhase::Charge => self.charge_target = target,
+            BossPhase::Minions => self.minions_due = true,
+            BossPhase::Rings => {
+                // Opens with a ring instead of a full interval of silence.
+                let duration = self.ring_timer.duration();
+                self.ring_timer.set_elapsed(duration);
+            }
+        }
+    }
+}
+
+impl GetBoundingRect for Boss {
+    fn get_bounding_rect(&self) -> BoundingRect {
+        BoundingRect {
+            x: self.position.x,
+            y: self.position.y,
+            width: BOSS_SIZE.width,
+            height: BOSS_SIZE.height,
+        }
+    }
+}
+
+impl GetPosition for Boss {
+    fn get_position(&self) -> Vec2 {
+        self.position
+    }
+}
+
+#[derive(Component)]
+struct BossHealthBar;
+
+#[derive(Component)]
+struct BossHealthFill;
+
+/// Spawns the boss above the screen, it flies in to its hover position.
+pub fn add_boss(commands: &mut Commands, texture_atlas: &EnemyTextureAtlas, config: &BossConfig) {
+    let position = Vec2::new(0.0, (WINDOW_SIZE.height + BOSS_SIZE.height) / 2.0);
+
+    commands
+        .spawn(SpriteSheetBundle {
+            sprite: TextureAtlasSprite {
+                color: BOSS_COLOR,
+                custom_size: Some(Vec2::new(BOSS_SIZE.width, BOSS_SIZE.height)),
+                ..default()
+            },
+            texture_atlas: Handle::clone(texture_atlas),
+            transform: Transform::from_xyz(position.x, position.y, POSITION_Z.enemy),
+            ..default()
+        })
+        .insert(EnemyAnimationTimer(Timer::from_seconds(
+            0.1,
+            TimerMode::Repeating,
+        )))
+        .insert(PreviousPosition(position))
+        .insert(Boss::new(config, position))
+        .insert(Health::new(config.health))
+        .insert(HitFlash::new(BOSS_COLOR));
+
+    commands
+        .spawn(SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(0.3, 0.0, 0.0, 0.8),
+                custom_size: Some(Vec2::new(HEALTH_BAR_SIZE.width, HEALTH_BAR_SIZE.height)),
+                ..default()
+            },
+            transform: Transform::from_xyz(
+                0.0,
+                WINDOW_SIZE.height / 2.0 - 16.0,
+                POSITION_Z.boss_health,
+            ),
+            ..default()
+        })
+        .insert(BossHealthBar)
+        .with_children(|parent| {
+            parent
+                .spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: BOSS_COLOR,
+                        custom_size: Some(Vec2::new(HEALTH_BAR_SIZE.width, HEALTH_BAR_SIZE.height)),
+                        anchor: Anchor::CenterLeft,
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(-HEALTH_BAR_SIZE.width / 2.0, 0.0, 0.1),
+                    ..default()
+                })
+                .insert(BossHealthFill);
+        });
+}
+
+fn fire_ring(commands: &mut Commands, position: Vec2, config: &GameConfig, offset: f32) {
+    let count = config.boss.ring_bullets;
+
+    for index in 0..count {
+        let angle = offset + TAU * index as f32 / count as f32;
+        add_enemy_bullet(
+            commands,
+            position,
+            Vec2::from_angle(angle) * config.enemy_bullet.speed,
+        );
+    }
+}
+
+fn update_boss(
+    config: Res<GameConfig>,
+    character_query: Query<&Character>,
+    mut query: Query<(&mut Boss, &Health, &mut PreviousPosition)>,
+    mut commands: Commands,
+) {
+    let delta = Duration::from_secs_f64(TIMESTEP);
+    let delta_seconds = TIMESTEP as f32;
+    let target = character_query
+        .get_single()
+        .ok()
+        .map(|character| character.position);
+
+    for (mut boss, health, mut previous_position) in query.iter_mut() {
+        previous_position.0 = boss.position;
+
+        if !health.is_alive() {
+            boss.speed = Vec2::ZERO;
+            continue;
+        }
+
+        boss.age += delta_seconds;
+
+        let phase_duration = Duration::from_secs_f32(config.boss.phase_duration);
+        if boss.phase_timer.duration() != phase_duration {
+            boss.phase_timer.set_duration(phase_duration);
+        }
+        if boss.phase_timer.tick(delta).finished() {
+            let phase = boss.phase.next();
+            boss.enter_phase(phase, target);
+        }
+
+        let charging =
+            boss.phase == BossPhase::Charge && boss.phase_timer.elapsed_secs() > CHARGE_WINDUP;
+
+        match boss.charge_target {
+            Some(charge_target) if charging => {
+                let step = config.boss.charge_speed * delta_seconds;
+                let offset = charge_target - boss.position;
+
+                if offset.length() <= step {
+                    boss.position = charge_target;
+                    boss.charge_target = None;
+                } else {
+                    boss.position += offset.normalize() * step;
+                }
+            }
+            Some(_) => {}
+            None => {
+                let home = Vec2::new((boss.age * 0.5).sin() * HOVER_RANGE, HOVER_HEIGHT);
+                let rate = (HOVER_RATE * delta_seconds).min(1.0);
+
+                boss.position = boss.position.lerp(home, rate);
+            }
+        }
+
+        boss.speed = (boss.position - previous_position.0) / delta_seconds;
+
+        // Below half health the rings come twice as often.
+        let enraged = health.fraction() <= 0.5;
+        if boss.phase != BossPhase::Rings && !enraged {
+            continue;
+        }
+
+        let mut ring_interval = config.boss.ring_interval;
+        if enraged {
+            ring_interval /= 2.0;
+        }
+        let ring_interval = Duration::from_secs_f32(ring_interval);
+        if boss.ring_timer.duration() != ring_interval {
+            boss.ring_timer.set_duration(ring_interval);
+        }
+
+        if boss.ring_timer.tick(delta).finished() && boss.position.y < WINDOW_SIZE.height / 2.0 {
+            fire_ring(&mut commands, boss.position, &config, boss.age);
+            boss.ring_timer.reset();
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_minions(
+    texture_atlas: Res<EnemyTextureAtlas>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    wave_query: Query<&Wave>,
+    mut query: Query<(&mut Boss, &Health)>,
+    mut enemy_count_query: Query<&mut EnemyCount>,
+    mut enemies_left_events: EventWriter<EnemiesLeftEvent>,
+    mut commands: Commands,
+) {
+    let Ok(wave) = wave_query.get_single() else {
+        return;
+    };
+
+    for (mut boss, health) in query.iter_mut() {
+        if !boss.minions_due {
+            continue;
+        }
+        boss.minions_due = false;
+
+        let count = config.boss.minion_count;
+        if !health.is_alive() || count == 0 {
+            continue;
+        }
+
+        add_enemies(
+            &mut commands,
+            &texture_atlas,
+            &mut rng,
+            &config.enemies,
+            wave.number,
+            count,
+            wave.speed_scale(&config.waves),
+        );
+
+        let mut enemy_count = enemy_count_query.single_mut();
+        enemy_count.count += count;
+
+        enemies_left_events.send(EnemiesLeftEvent {
+            enemies_left: enemy_count.count,
+        });
+    }
+}
+
+/// Chains explosions spiralling out over the boss, then removes it.
+fn update_boss_death(
+    mut query: Query<(Entity, &mut Boss, &Health)>,
+    mut add_explosion_events: EventWriter<AddExplosionEvent>,
+    mut commands: Commands,
+    loader: Res<LoaderState>,
+    audio: Res<Audio>,
+) {
+    for (entity, mut boss, health) in query.iter_mut() {
+        if health.is_alive()
+            || !boss
+                .blast_timer
+                .tick(Duration::from_secs_f64(TIMESTEP))
+                .just_finished()
+        {
+            continue;
+        }
+
+        boss.blasts += 1;
+
+        let angle = boss.blasts as f32 * 2.4;
+        let radius = BOSS_SIZE.width / 2.0 * boss.blasts as f32 / BLAST_COUNT as f32;
+        add_explosion_events.send(AddExplosionEvent {
+            position: boss.position + Vec2::from_angle(angle) * radius,
+        });
+
+        if boss.blasts % 3 == 0 {
+            audio.play(loader.explosion_sound.clone());
+        }
+
+        if boss.blasts >= BLAST_COUNT {
+            add_explosion_events.send(AddExplosionEvent {
+                position: boss.position,
+            });
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Counts the boss out once it's killed, its death sequence plays from there.
+fn defeat_boss(
+    mut damage_events: EventReader<DamageEvent>,
+    boss_query: Query<(), With<Boss>>,
+    mut wave_query: Query<&mut Wave>,
+    mut enemy_count_query: Query<&mut EnemyCount>,
+    mut enemies_left_events: EventWriter<EnemiesLeftEvent>,
+) {
+    for damage_event in damage_events.iter() {
+        if !damage_event.fatal || !boss_query.contains(damage_event.entity) {
+            continue;
+        }
+
+        wave_query.single_mut().boss_defeated = true;
+
+        let mut enemy_count = enemy_count_query.single_mut();
+        enemy_count.remove();
+        enemies_left_events.send(EnemiesLeftEvent {
+            enemies_left: enemy_count.count,
+        });
+    }
+}
+
+fn update_boss_health_bar(
+    boss_query: Query<&Health, With<Boss>>,
+    mut fill_query: Query<&mut Transform, With<BossHealthFill>>,
+    bar_query: Query<Entity, With<BossHealthBar>>,
+    mut commands: Commands,
+) {
+    let Ok(health) = boss_query.get_single() else {
+        for entity in bar_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    };
+
+    for mut transform in fill_query.iter_mut() {
+        transform.scale.x = health.fraction();
+    }
+}
+
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(AppState::Main)
+                .with_system(interpolate::<Boss>)
+                .with_system(update_boss_health_bar),
+        )
+        .add_system_set_to_stage(
+            FixedUpdateStage,
+            on_fixed_update(AppState::Main)
+                .label(FixedUpdateLabel::Movement)
+                .with_system(update_boss)
+                .with_system(spawn_minions.after(update_boss))
+                .with_system(update_boss_death),
+        )
+        .add_system_set_to_stage(
+            FixedUpdateStage,
+            on_fixed_update(AppState::Main)
+                .after(FixedUpdateLabel::Damage)
+                .with_system(defeat_boss),
+        )
+        .add_system_set(
+            SystemSet::on_exit(AppState::Main)
+                .with_system(despawn_with::<Boss>)
+                .with_system(despawn_with::<BossHealthBar>),
+        );
+    }
+}
@@ -1,138 +1,258 @@
-use crate::utils;
 use crate::{
+    boss::Boss,
     consts::{POSITION_Z, WINDOW_SIZE},
-    events::TransformEvent,
-    state::{AppState, LoaderState},
-    utils::{BoundingRect, GetBoundingRect},
+    enemies::Enemy,
+    events::{CollisionEvent, CollisionKind, DamageEvent},
+    health::{Damage, Health},
+    score::Score,
+    state::{despawn_with, AppState},
+    stats::Stats,
+    timestep::{
+        interpolate, on_fixed_update, FixedUpdateLabel, FixedUpdateStage, PreviousPosition,
+        TIMESTEP,
+    },
+    utils::{steer, BoundingRect, Collider, GetBoundingRect, GetPosition},
+    weapon::WeaponKind,
 };
-use bevy::{prelude::*, utils::Duration};
+use bevy::prelude::*;
 
-static BULLET_SPEED: f32 = 300.0;
-static BULLET_SIZE: utils::Size = utils::Size {
-    width: 5.0,
-    height: 20.0,
-};
-static BULLET_INITIAL_DELAY: f32 = 0.1;
-static BULLET_DELAY: f32 = 0.5;
-
-#[derive(Component)]
-pub struct BulletPool {
-    timer: Timer,
-    position: Vec2,
-    rotation: f32,
-}
+static HOMING_TURN_RATE: f32 = 4.0;
 
 #[derive(Component)]
 pub struct Bullet {
     position: Vec2,
     speed: Vec2,
+    weapon: WeaponKind,
+    shot: u32,
+    targets: Vec<Entity>,
+}
+
+impl Bullet {
+    pub fn new(position: Vec2, speed: Vec2) -> Self {
+        Self::fired(WeaponKind::Single, 0, position, speed)
+    }
+
+    /// A bullet of the `shot`-th trigger pull of `weapon`, the projectiles of a spread
+    /// share it.
+    pub fn fired(weapon: WeaponKind, shot: u32, position: Vec2, speed: Vec2) -> Self {
+        Self {
+            position,
+            speed,
+            weapon,
+            shot,
+            targets: Vec::new(),
+        }
+    }
+
+    pub fn speed(&self) -> Vec2 {
+        self.speed
+    }
+
+    pub fn weapon(&self) -> WeaponKind {
+        self.weapon
+    }
+
+    /// Whether it already hit anything, a bullet that did is not a miss.
+    pub fn has_hit(&self) -> bool {
+        !self.targets.is_empty()
+    }
+
+    /// Whether it can't hit anything else, only piercing bullets go on after a hit.
+    pub fn is_spent(&self) -> bool {
+        self.has_hit() && !self.weapon.pierces()
+    }
+
+    /// Whether `target` can still be hit, a piercing bullet goes through everything once.
+    pub fn can_hit(&self, target: Entity) -> bool {
+        !self.is_spent() && !self.targets.contains(&target)
+    }
+
+    /// Records a hit on `target`. Returns `false` when a piercing bullet already went
+    /// through it.
+    pub fn hit(&mut self, target: Entity) -> bool {
+        if self.targets.contains(&target) {
+            return false;
+        }
+
+        self.targets.push(target);
+        true
+    }
 }
 
 impl GetBoundingRect for Bullet {
     fn get_bounding_rect(&self) -> BoundingRect {
+        let size = self.weapon.projectile_size();
+
         BoundingRect {
             x: self.position.x,
             y: self.position.y,
-            width: BULLET_SIZE.width,
-            height: BULLET_SIZE.width,
+            width: size.width,
+            height: size.height,
         }
     }
-}
 
-fn setup_bullet_pool(mut commands: Commands) {
-    commands.spawn_empty().insert(BulletPool {
-        timer: Timer::from_seconds(BULLET_INITIAL_DELAY, TimerMode::Once),
-        position: Vec2::new(0.0, 0.0),
-        rotation: 0.0,
-    });
+    /// The sprite's rounded outline lying along the bullet's speed, at half the size
+    /// like every other collider.
+    fn get_collider(&self) -> Collider {
+        let size = self.weapon.projectile_size();
+        let half_length = (size.height - size.width).max(0.0) / 4.0;
+        let direction = self.speed.normalize_or_zero();
+
+        Collider::Capsule {
+            start: self.position - direction * half_length,
+            end: self.position + direction * half_length,
+            radius: size.width / 4.0,
+        }
+    }
 }
 
-fn handle_transform(
-    mut transform_events: EventReader<TransformEvent>,
-    mut query: Query<&mut BulletPool>,
-) {
-    for transform_event in transform_events.iter() {
-        let mut bullet_pool = query.single_mut();
-        bullet_pool.position.x = transform_event.position.x;
-        bullet_pool.position.y = transform_event.position.y;
-        bullet_pool.rotation = transform_event.rotation;
+impl GetPosition for Bullet {
+    fn get_position(&self) -> Vec2 {
+        self.position
     }
 }
 
-fn update_bullet_pool(
-    time: Res<Time>,
-    mouse_button_input: Res<Input<MouseButton>>,
-    loader: ResMut<LoaderState>,
-    audio: Res<Audio>,
-    mut query: Query<&mut BulletPool>,
-    mut commands: Commands,
-) {
-    let just_pressed = mouse_button_input.just_pressed(MouseButton::Left);
-
-    let mut bullet_pool = query.single_mut();
-    if bullet_pool.timer.tick(time.delta()).finished() {
-        if just_pressed {
-            let bullet_position = Vec2::new(bullet_pool.position.x, bullet_pool.position.y);
-            let bullet_rotation = bullet_pool.rotation;
-            let bullet_speed = Vec2::new(
-                -BULLET_SPEED * bullet_rotation.sin(),
-                BULLET_SPEED * bullet_rotation.cos(),
-            );
-
-            commands
-                .spawn(SpriteBundle {
-                    sprite: Sprite {
-                        color: Color::rgba(1.0, 0.0, 1.0, 0.8),
-                        custom_size: Some(Vec2::new(BULLET_SIZE.width, BULLET_SIZE.height)),
-                        ..default()
-                    },
-                    transform: Transform::from_xyz(
-                        bullet_position.x,
-                        bullet_position.y,
-                        POSITION_Z.bullet,
-                    )
-                    .with_rotation(Quat::from_rotation_z(bullet_rotation)),
-                    ..default()
-                })
-                .insert(Bullet {
-                    position: bullet_position,
-                    speed: bullet_speed,
-                });
-
-            if bullet_pool.timer.duration() == Duration::from_secs_f32(BULLET_INITIAL_DELAY) {
-                bullet_pool
-                    .timer
-                    .set_duration(Duration::from_secs_f32(BULLET_DELAY));
-            }
+fn get_rotation_z(speed: &Vec2) -> f32 {
+    -speed.x.atan2(speed.y)
+}
 
-            bullet_pool.timer.reset();
+pub fn add_bullet(
+    commands: &mut Commands,
+    weapon: WeaponKind,
+    shot: u32,
+    damage: Damage,
+    position: Vec2,
+    speed: Vec2,
+) {
+    let size = weapon.projectile_size();
 
-            audio.play(loader.bullet_sound.clone());
-        }
-    }
+    commands
+        .spawn(SpriteBundle {
+            sprite: Sprite {
+                color: weapon.color(),
+                custom_size: Some(Vec2::new(size.width, size.height)),
+                ..default()
+            },
+            transform: Transform::from_xyz(position.x, position.y, POSITION_Z.bullet)
+                .with_rotation(Quat::from_rotation_z(get_rotation_z(&speed))),
+            ..default()
+        })
+        .insert(PreviousPosition(position))
+        .insert(Bullet::fired(weapon, shot, position, speed))
+        .insert(damage);
 }
 
 fn update_bullet(
-    time: Res<Time>,
-    mut query: Query<(Entity, &mut Bullet, &mut Transform)>,
+    enemy_query: Query<(&Enemy, &Health)>,
+    boss_query: Query<(&Boss, &Health)>,
+    mut query: Query<(
+        Entity,
+        &mut Bullet,
+        &mut PreviousPosition,
+        Option<&mut Transform>,
+    )>,
+    mut score: ResMut<Score>,
     mut commands: Commands,
 ) {
-    let delta_seconds = time.delta_seconds();
+    let delta_seconds = TIMESTEP as f32;
+
+    for (entity, mut bullet, mut previous_position, transform) in query.iter_mut() {
+        let size = bullet.weapon.projectile_size();
 
-    for (entity, mut bullet, mut transform) in query.iter_mut() {
-        if bullet.position.x < -WINDOW_SIZE.width / 2.0 - BULLET_SIZE.width
-            || bullet.position.x > WINDOW_SIZE.width / 2.0 + BULLET_SIZE.width
-            || bullet.position.y < -WINDOW_SIZE.height / 2.0 - BULLET_SIZE.height
-            || bullet.position.y > WINDOW_SIZE.height / 2.0 + BULLET_SIZE.height
+        if bullet.position.x < -WINDOW_SIZE.width / 2.0 - size.width
+            || bullet.position.x > WINDOW_SIZE.width / 2.0 + size.width
+            || bullet.position.y < -WINDOW_SIZE.height / 2.0 - size.height
+            || bullet.position.y > WINDOW_SIZE.height / 2.0 + size.height
         {
             commands.entity(entity).despawn();
+            if !bullet.has_hit() {
+                score.miss(bullet.shot);
+            }
         } else {
+            previous_position.0 = bullet.position;
+
+            if bullet.weapon.homes() {
+                let position = bullet.position;
+                let target = enemy_query
+                    .iter()
+                    .filter(|(_, health)| health.is_alive())
+                    .map(|(enemy, _)| enemy.position)
+                    .chain(
+                        boss_query
+                            .iter()
+                            .filter(|(_, health)| health.is_alive())
+                            .map(|(boss, _)| boss.position),
+                    )
+                    .min_by(|lhs, rhs| {
+                        lhs.distance_squared(position)
+                            .total_cmp(&rhs.distance_squared(position))
+                    });
+
+                if let Some(target) = target {
+                    bullet.speed = steer(
+                        position,
+                        bullet.speed,
+                        target,
+                        HOMING_TURN_RATE * delta_seconds,
+                    );
+                    if let Some(mut transform) = transform {
+                        transform.rotation = Quat::from_rotation_z(get_rotation_z(&bullet.speed));
+                    }
+                }
+            }
+
             bullet.position.x += bullet.speed.x * delta_seconds;
             bullet.position.y += bullet.speed.y * delta_seconds;
+        }
+    }
+}
 
-            transform.translation.x = bullet.position.x;
-            transform.translation.y = bullet.position.y;
+/// Takes the damage of bullets off what they hit. A bullet that can't hit anything
+/// else is gone, one hitting something already dead goes on.
+fn hit_targets(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut bullet_query: Query<(&mut Bullet, &Damage)>,
+    mut target_query: Query<(&mut Health, AnyOf<(&Enemy, &Boss)>)>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut stats: ResMut<Stats>,
+    mut score: ResMut<Score>,
+    mut commands: Commands,
+) {
+    for collision_event in collision_events.iter() {
+        if collision_event.kind != CollisionKind::Shot {
+            continue;
         }
+
+        let (Ok((mut bullet, damage)), Ok((mut health, (enemy, boss)))) = (
+            bullet_query.get_mut(collision_event.a),
+            target_query.get_mut(collision_event.b),
+        ) else {
+            continue;
+        };
+
+        if !health.is_alive() || !bullet.can_hit(collision_event.b) {
+            continue;
+        }
+
+        if !bullet.has_hit() {
+            stats.hits += 1;
+        }
+        bullet.hit(collision_event.b);
+        score.hit();
+        if bullet.is_spent() {
+            commands.entity(collision_event.a).despawn();
+        }
+
+        let position = enemy
+            .map(|enemy| enemy.position)
+            .or(boss.map(|boss| boss.position))
+            .unwrap_or_default();
+        damage_events.send(DamageEvent {
+            entity: collision_event.b,
+            position,
+            damage: damage.0,
+            fatal: health.take(*damage),
+        });
     }
 }
 
@@ -140,9 +260,20 @@ pub struct BulletPlugin;
 
 impl Plugin for BulletPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system_set(SystemSet::on_enter(AppState::Main).with_system(setup_bullet_pool))
-            .add_system_set(SystemSet::on_update(AppState::Main).with_system(update_bullet_pool))
-            .add_system_set(SystemSet::on_update(AppState::Main).with_system(update_bullet))
-            .add_system_set(SystemSet::on_update(AppState::Main).with_system(handle_transform));
+        app.add_system_set(SystemSet::on_update(AppState::Main).with_system(interpolate::<Bullet>))
+            .add_system_set_to_stage(
+                FixedUpdateStage,
+                on_fixed_update(AppState::Main)
+                    .label(FixedUpdateLabel::Movement)
+                    .with_system(update_bullet),
+            )
+            .add_system_set_to_stage(
+                FixedUpdateStage,
+                on_fixed_update(AppState::Main)
+                    .label(FixedUpdateLabel::Damage)
+                    .after(FixedUpdateLabel::Collision)
+                    .with_system(hit_targets),
+            )
+            .add_system_set(SystemSet::on_exit(AppState::Main).with_system(despawn_with::<Bullet>));
     }
 }
@@ -1,11 +1,23 @@
 use crate::utils;
 use crate::{
+    boss::Boss,
+    config::GameConfig,
     consts::{POSITION_Z, WINDOW_SIZE},
-    events::{CharacterLifesEvent, TransformEvent},
-    state::{AppState, LoaderState},
-    utils::{BoundingRect, GetBoundingRect, IsActive, SetSpeed},
+    controls::{Action, ActionInput},
+    enemies::Enemy,
+    enemy_bullet::EnemyBullet,
+    events::{
+        CharacterHitEvent, CharacterLifesEvent, CollisionEvent, CollisionKind, TransformEvent,
+    },
+    state::{despawn_with, AppState, LoaderState},
+    timestep::{
+        interpolate, on_fixed_update, FixedUpdateLabel, FixedUpdateStage, PreviousPosition,
+        TIMESTEP,
+    },
+    utils::{BoundingRect, Collider, GetBoundingRect, GetPosition, IsActive, SetSpeed},
+    weapon::{Weapon, WeaponKind},
 };
-use bevy::{input::keyboard::KeyCode, prelude::*};
+use bevy::{prelude::*, utils::Duration};
 
 #[derive(Component, Deref, DerefMut)]
 struct CharacterAnimationTimer(Timer);
@@ -13,7 +25,7 @@ struct CharacterAnimationTimer(Timer);
 #[derive(Component, Deref, DerefMut)]
 pub struct CharacterActive(bool);
 #[derive(Component, Deref, DerefMut)]
-struct CharacterInactiveTimer(Timer);
+pub struct CharacterInactiveTimer(Timer);
 
 #[derive(Component)]
 pub struct Character {
@@ -29,20 +41,18 @@ pub struct CharacterLifes {
 
 impl CharacterLifes {
     pub fn decrease(&mut self) {
-        self.lifes = self.lifes - 1;
+        self.lifes -= 1;
+    }
+
+    pub fn increase(&mut self) {
+        self.lifes += 1;
     }
 }
 
-static CHARACTER_LIFES: u32 = 3;
 static CHARACTER_SIZE: utils::Size = utils::Size {
     width: 128.0,
     height: 128.0,
 };
-static MAX_SPEED: f32 = 150.0;
-static FRICTION: f32 = 0.96;
-
-static INACTIVE_DURATION: f32 = 3.0;
-
 const CHARACTER_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 1.0);
 
 impl Default for Character {
@@ -55,6 +65,21 @@ impl Default for Character {
     }
 }
 
+impl Character {
+    /// Turns the character towards `target`, given in world coordinates.
+    pub fn aim_at(&mut self, target: Vec2) {
+        self.mouse = target + Vec2::new(WINDOW_SIZE.width / 2.0, WINDOW_SIZE.height / 2.0);
+    }
+
+    /// Rotation of the sprite, facing the mouse.
+    pub fn rotation_z(&self) -> f32 {
+        let delta_x = self.mouse.x - self.position.x - WINDOW_SIZE.width / 2.0;
+        let delta_y = self.mouse.y - self.position.y - WINDOW_SIZE.height / 2.0;
+
+        -delta_x.atan2(delta_y)
+    }
+}
+
 impl GetBoundingRect for Character {
     fn get_bounding_rect(&self) -> BoundingRect {
         BoundingRect {
@@ -64,6 +89,20 @@ impl GetBoundingRect for Character {
             height: CHARACTER_SIZE.height,
         }
     }
+
+    fn get_collider(&self) -> Collider {
+        Collider::OrientedBox {
+            center: self.position,
+            half_size: Vec2::new(CHARACTER_SIZE.width, CHARACTER_SIZE.height) / 4.0,
+            rotation: self.rotation_z(),
+        }
+    }
+}
+
+impl GetPosition for Character {
+    fn get_position(&self) -> Vec2 {
+        self.position
+    }
 }
 
 impl SetSpeed for Character {
@@ -82,11 +121,102 @@ impl IsActive for CharacterActive {
     }
 }
 
+/// Makes the character invulnerable for at least `seconds`, a longer invulnerability
+/// that is already running is kept.
+pub fn set_inactive(
+    character_active: &mut CharacterActive,
+    inactive_timer: &mut CharacterInactiveTimer,
+    seconds: f32,
+) {
+    let remaining = if character_active.get_active() {
+        Duration::ZERO
+    } else {
+        inactive_timer
+            .duration()
+            .saturating_sub(inactive_timer.elapsed())
+    };
+    let duration = Duration::from_secs_f32(seconds);
+
+    if duration > remaining {
+        inactive_timer.set_duration(duration);
+        inactive_timer.reset();
+    }
+
+    character_active.set_active(false);
+}
+
+/// Takes a life for a hit and makes the character briefly invulnerable, knocked back by
+/// whatever hit it. It's gone after the last life.
+#[allow(clippy::too_many_arguments)]
+fn take_hits(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut character_query: Query<(
+        &mut Character,
+        &mut CharacterActive,
+        &mut CharacterInactiveTimer,
+    )>,
+    mut character_lifes_query: Query<&mut CharacterLifes>,
+    hitter_query: Query<AnyOf<(&Enemy, &Boss, &EnemyBullet)>>,
+    mut character_lifes_events: EventWriter<CharacterLifesEvent>,
+    mut character_hit_events: EventWriter<CharacterHitEvent>,
+    config: Res<GameConfig>,
+    mut commands: Commands,
+) {
+    for collision_event in collision_events.iter() {
+        if !matches!(
+            collision_event.kind,
+            CollisionKind::EnemyShot | CollisionKind::Ram
+        ) {
+            continue;
+        }
+
+        let Ok((mut character, mut character_active, mut inactive_timer)) =
+            character_query.get_mut(collision_event.b)
+        else {
+            continue;
+        };
+        if !character_active.get_active() {
+            continue;
+        }
+
+        set_inactive(
+            &mut character_active,
+            &mut inactive_timer,
+            config.character.inactive_duration,
+        );
+        let mut character_lifes = character_lifes_query.single_mut();
+        character_lifes.decrease();
+        character_lifes_events.send(CharacterLifesEvent {
+            character_lifes: character_lifes.lifes,
+        });
+
+        let fatal = character_lifes.lifes == 0;
+        if fatal {
+            commands.entity(collision_event.b).despawn();
+        } else {
+            let knockback = match hitter_query.get(collision_event.a) {
+                Ok((Some(enemy), _, _)) => enemy.speed,
+                Ok((_, Some(boss), _)) => boss.speed,
+                Ok((_, _, Some(enemy_bullet))) => enemy_bullet.speed(),
+                _ => Vec2::ZERO,
+            };
+            character.set_speed(knockback * 0.02);
+        }
+
+        character_hit_events.send(CharacterHitEvent {
+            hitter: collision_event.a,
+            position: character.position,
+            fatal,
+        });
+    }
+}
+
 fn setup_lifes(
     mut commands: Commands,
+    config: Res<GameConfig>,
     mut character_lifes_events: EventWriter<CharacterLifesEvent>,
 ) {
-    let lifes = CHARACTER_LIFES;
+    let lifes = config.character.lifes;
 
     commands.spawn_empty().insert(CharacterLifes { lifes });
 
@@ -95,9 +225,24 @@ fn setup_lifes(
     });
 }
 
+fn reset_lifes(
+    config: Res<GameConfig>,
+    mut query: Query<&mut CharacterLifes>,
+    mut character_lifes_events: EventWriter<CharacterLifesEvent>,
+) {
+    for mut character_lifes in query.iter_mut() {
+        character_lifes.lifes = config.character.lifes;
+
+        character_lifes_events.send(CharacterLifesEvent {
+            character_lifes: character_lifes.lifes,
+        });
+    }
+}
+
 fn setup(
     mut commands: Commands,
     loader: Res<LoaderState>,
+    config: Res<GameConfig>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
 ) {
     let texture_handle = loader.character_image.clone();
@@ -125,11 +270,13 @@ fn setup(
             0.05,
             TimerMode::Repeating,
         )))
+        .insert(PreviousPosition(Vec2::ZERO))
         .insert(CharacterActive(true))
         .insert(CharacterInactiveTimer(Timer::from_seconds(
-            INACTIVE_DURATION,
+            config.character.inactive_duration,
             TimerMode::Once,
         )))
+        .insert(Weapon::new(WeaponKind::Single, config.bullet.initial_delay))
         .insert(Character::default());
 }
 
@@ -155,12 +302,7 @@ fn transform_changed(
     mut query: Query<(&Character, &mut Transform), Changed<Character>>,
 ) {
     for (character, mut transform) in query.iter_mut() {
-        transform.translation.x = character.position.x;
-        transform.translation.y = character.position.y;
-
-        let delta_x = character.mouse.x - character.position.x - WINDOW_SIZE.width / 2.0;
-        let delta_y = character.mouse.y - character.position.y - WINDOW_SIZE.height / 2.0;
-        let rotation_z = -delta_x.atan2(delta_y);
+        let rotation_z = character.rotation_z();
 
         transform.rotation = Quat::from_rotation_z(rotation_z);
 
@@ -172,18 +314,10 @@ fn transform_changed(
 }
 
 fn active_changed(
-    mut query: Query<
-        (
-            &CharacterActive,
-            &mut CharacterInactiveTimer,
-            &mut TextureAtlasSprite,
-        ),
-        Changed<CharacterActive>,
-    >,
+    mut query: Query<(&CharacterActive, &mut TextureAtlasSprite), Changed<CharacterActive>>,
 ) {
-    for (character_active, mut inactive_timer, mut sprite) in query.iter_mut() {
-        if character_active.get_active() == false {
-            inactive_timer.reset();
+    for (character_active, mut sprite) in query.iter_mut() {
+        if !character_active.get_active() {
             sprite.color.set_a(0.8);
         }
     }
@@ -198,9 +332,7 @@ fn inactive_timer_changed(
     )>,
 ) {
     for (mut character_active, mut inactive_timer, mut sprite) in query.iter_mut() {
-        if character_active.get_active() == false
-            && inactive_timer.tick(time.delta()).just_finished()
-        {
+        if !character_active.get_active() && inactive_timer.tick(time.delta()).just_finished() {
             character_active.set_active(true);
             sprite.color.set_a(1.0);
         }
@@ -219,28 +351,37 @@ fn follow_mouse(
     }
 }
 
-fn follow_keyboard(
-    time: Res<Time>,
-    keyboard_input: Res<Input<KeyCode>>,
-    mut query: Query<&mut Character>,
+/// Moves the character with the move actions or an analog stick, on a gamepad or on
+/// screen. The stick scales the speed with how far it is pushed.
+fn follow_input(
+    actions: ActionInput,
+    config: Res<GameConfig>,
+    mut query: Query<(&mut Character, &mut PreviousPosition)>,
 ) {
-    for mut character in query.iter_mut() {
-        let delta_seconds = time.delta_seconds();
+    for (mut character, mut previous_position) in query.iter_mut() {
+        let delta_seconds = TIMESTEP as f32;
+        let max_speed = config.character.max_speed;
+        previous_position.0 = character.position;
+
+        let stick = actions.move_stick();
+        if stick != Vec2::ZERO {
+            character.speed = stick * max_speed * delta_seconds;
+        }
 
-        if keyboard_input.pressed(KeyCode::Up) || keyboard_input.pressed(KeyCode::W) {
-            character.speed.y = MAX_SPEED * delta_seconds;
+        if actions.pressed(Action::MoveUp) {
+            character.speed.y = max_speed * delta_seconds;
         }
 
-        if keyboard_input.pressed(KeyCode::Down) || keyboard_input.pressed(KeyCode::S) {
-            character.speed.y = -MAX_SPEED * delta_seconds;
+        if actions.pressed(Action::MoveDown) {
+            character.speed.y = -max_speed * delta_seconds;
         }
 
-        if keyboard_input.pressed(KeyCode::Right) || keyboard_input.pressed(KeyCode::D) {
-            character.speed.x = MAX_SPEED * delta_seconds;
+        if actions.pressed(Action::MoveRight) {
+            character.speed.x = max_speed * delta_seconds;
         }
 
-        if keyboard_input.pressed(KeyCode::Left) || keyboard_input.pressed(KeyCode::A) {
-            character.speed.x = -MAX_SPEED * delta_seconds;
+        if actions.pressed(Action::MoveLeft) {
+            character.speed.x = -max_speed * delta_seconds;
         }
 
         character.position.x += character.speed.x;
@@ -261,7 +402,7 @@ fn follow_keyboard(
             character.position.y = -max_y;
         }
 
-        character.speed *= FRICTION;
+        character.speed *= config.character.friction;
     }
 }
 
@@ -270,7 +411,11 @@ pub struct CharacterPlugin;
 impl Plugin for CharacterPlugin {
     fn build(&self, app: &mut App) {
         app.add_startup_system(setup_lifes)
-            .add_system_set(SystemSet::on_enter(AppState::Main).with_system(setup))
+            .add_system_set(
+                SystemSet::on_enter(AppState::Main)
+                    .with_system(setup)
+                    .with_system(reset_lifes),
+            )
             .add_system_set(SystemSet::on_update(AppState::Main).with_system(animate))
             .add_system_set(SystemSet::on_update(AppState::Main).with_system(transform_changed))
             .add_system_set(SystemSet::on_update(AppState::Main).with_system(active_changed))
@@ -278,6 +423,24 @@ impl Plugin for CharacterPlugin {
                 SystemSet::on_update(AppState::Main).with_system(inactive_timer_changed),
             )
             .add_system_set(SystemSet::on_update(AppState::Main).with_system(follow_mouse))
-            .add_system_set(SystemSet::on_update(AppState::Main).with_system(follow_keyboard));
+            .add_system_set(
+                SystemSet::on_update(AppState::Main).with_system(interpolate::<Character>),
+            )
+            .add_system_set_to_stage(
+                FixedUpdateStage,
+                on_fixed_update(AppState::Main)
+                    .label(FixedUpdateLabel::Movement)
+                    .with_system(follow_input),
+            )
+            .add_system_set_to_stage(
+                FixedUpdateStage,
+                on_fixed_update(AppState::Main)
+                    .label(FixedUpdateLabel::Damage)
+                    .after(FixedUpdateLabel::Collision)
+                    .with_system(take_hits),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Main).with_system(despawn_with::<Character>),
+            );
     }
 }
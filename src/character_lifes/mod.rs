@@ -1,17 +1,22 @@
 use crate::{
+    bomb::BombCharges,
     character::CharacterLifes,
     consts::{POSITION_Z, WINDOW_SIZE},
-    events::CharacterLifesEvent,
-    state::{AppState, LoaderState},
+    events::{BombChargesEvent, CharacterLifesEvent},
+    state::{despawn_with, AppState, LoaderState},
 };
 use bevy::prelude::*;
 
 #[derive(Component)]
 struct CharacterLifesText;
 
+#[derive(Component)]
+struct BombChargesText;
+
 fn add_character_lifes_text(
     mut commands: Commands,
     character_lifes_query: Query<&CharacterLifes>,
+    bomb_charges_query: Query<&BombCharges>,
     loader: Res<LoaderState>,
 ) {
     let style = TextStyle {
@@ -21,6 +26,7 @@ fn add_character_lifes_text(
     };
 
     let character_lifes = character_lifes_query.single();
+    let bomb_charges = bomb_charges_query.single();
 
     commands
         .spawn(Text2dBundle {
@@ -34,7 +40,7 @@ fn add_character_lifes_text(
                         value: character_lifes.lifes.to_string(),
                         style: TextStyle {
                             color: Color::TURQUOISE,
-                            ..style
+                            ..style.clone()
                         },
                     },
                 ],
@@ -51,6 +57,36 @@ fn add_character_lifes_text(
             ..default()
         })
         .insert(CharacterLifesText);
+
+    commands
+        .spawn(Text2dBundle {
+            text: Text {
+                sections: vec![
+                    TextSection {
+                        value: "Bombs:".to_string(),
+                        style: style.clone(),
+                    },
+                    TextSection {
+                        value: bomb_charges.charges.to_string(),
+                        style: TextStyle {
+                            color: Color::ORANGE_RED,
+                            ..style
+                        },
+                    },
+                ],
+                alignment: TextAlignment {
+                    horizontal: HorizontalAlign::Left,
+                    vertical: VerticalAlign::Top,
+                },
+            },
+            transform: Transform::from_xyz(
+                -WINDOW_SIZE.width / 2.0,
+                WINDOW_SIZE.height / 2.0 - 16.0 * 5.0,
+                POSITION_Z.character_lifes,
+            ),
+            ..default()
+        })
+        .insert(BombChargesText);
 }
 
 fn update_character_lifes_text(
@@ -64,6 +100,17 @@ fn update_character_lifes_text(
     }
 }
 
+fn update_bomb_charges_text(
+    mut bomb_charges_events: EventReader<BombChargesEvent>,
+    mut query: Query<&mut Text, With<BombChargesText>>,
+) {
+    for bomb_charges_event in bomb_charges_events.iter() {
+        for mut text in query.iter_mut() {
+            text.sections[1].value = format!("{}", bomb_charges_event.charges);
+        }
+    }
+}
+
 pub struct CharacterLifesTextPlugin;
 
 impl Plugin for CharacterLifesTextPlugin {
@@ -72,7 +119,14 @@ impl Plugin for CharacterLifesTextPlugin {
             SystemSet::on_enter(AppState::Main).with_system(add_character_lifes_text),
         )
         .add_system_set(
-            SystemSet::on_update(AppState::Main).with_system(update_character_lifes_text),
+            SystemSet::on_update(AppState::Main)
+                .with_system(update_character_lifes_text)
+                .with_system(update_bomb_charges_text),
+        )
+        .add_system_set(
+            SystemSet::on_exit(AppState::Main)
+                .with_system(despawn_with::<CharacterLifesText>)
+                .with_system(despawn_with::<BombChargesText>),
         );
     }
 }
@@ -0,0 +1,38 @@
+use std::path::PathBuf;
+
+/// Command line options of the game binary.
+///
+/// ```
+/// use stars_rs::cli::Args;
+///
+/// fn main() {
+///     let args = Args::parse(["stars_rs", "--seed", "42", "--replay", "run.ron"].map(String::from));
+///     assert_eq!(args.seed, Some(42));
+///     assert_eq!(args.replay, Some("run.ron".into()));
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct Args {
+    pub seed: Option<u64>,
+    /// Replay file to play back instead of taking input from the player.
+    pub replay: Option<PathBuf>,
+}
+
+impl Args {
+    /// Parses `--seed <u64>` and `--replay <file>`, ignoring anything it doesn't
+    /// recognize.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Self {
+        let mut result = Self::default();
+        let mut args = args.into_iter().skip(1);
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => result.seed = args.next().and_then(|seed| seed.parse().ok()),
+                "--replay" => result.replay = args.next().map(PathBuf::from),
+                _ => {}
+            }
+        }
+
+        result
+    }
+}
@@ -1,94 +1,177 @@
 use crate::{
+    boss::Boss,
     bullet::Bullet,
-    character::{Character, CharacterActive, CharacterLifes},
-    enemies::{Enemy, EnemyCount},
-    events::{AddExplosionEvent, CharacterLifesEvent, EnemiesLeftEvent},
-    state::{AppState, LoaderState},
-    utils::{hit_test, GetBoundingRect, IsActive, SetSpeed},
+    character::{Character, CharacterActive},
+    enemies::Enemy,
+    enemy_bullet::EnemyBullet,
+    events::{CollisionEvent, CollisionKind},
+    health::Health,
+    power_ups::PowerUp,
+    spatial_hash::{rebuild, SpatialHash},
+    state::AppState,
+    timestep::{on_fixed_update, FixedUpdateLabel, FixedUpdateStage, PreviousPosition},
+    utils::{Collider, GetBoundingRect, GetPosition, IsActive},
 };
 use bevy::prelude::*;
 
+/// Collider of a projectile where the last tick started, and how far it went since. A
+/// projectile without a previous position hasn't moved.
+fn path<T: GetBoundingRect + GetPosition>(
+    projectile: &T,
+    previous_position: Option<&PreviousPosition>,
+) -> (Collider, Vec2) {
+    let motion = previous_position.map_or(Vec2::ZERO, |previous_position| {
+        projectile.get_position() - previous_position.0
+    });
+
+    (projectile.get_collider().translated(-motion), motion)
+}
+
+/// Enemies and the boss running into the character. While the character is inactive
+/// nothing touches it.
 fn check_character_collision(
-    mut character_query: Query<(Entity, &mut Character, &mut CharacterActive)>,
-    mut character_lifes_query: Query<&mut CharacterLifes>,
-    mut character_lifes_events: EventWriter<CharacterLifesEvent>,
-    mut add_explosion_events: EventWriter<AddExplosionEvent>,
-    mut commands: Commands,
+    character_query: Query<(Entity, &Character, &CharacterActive)>,
+    enemy_grid: Res<SpatialHash<Enemy>>,
     enemy_query: Query<&Enemy>,
-    loader: ResMut<LoaderState>,
-    audio: Res<Audio>,
+    boss_query: Query<(Entity, &Boss, &Health)>,
+    mut collision_events: EventWriter<CollisionEvent>,
 ) {
-    for (character_entity, mut character, mut character_active) in character_query.iter_mut() {
-        for enemy in enemy_query.iter() {
-            if character_active.get_active()
-                && hit_test(character.get_bounding_rect(), enemy.get_bounding_rect())
-            {
-                character_active.set_active(false);
-                let mut character_lifes = character_lifes_query.single_mut();
-                character_lifes.decrease();
-                character_lifes_events.send(CharacterLifesEvent {
-                    character_lifes: character_lifes.lifes,
-                });
-
-                if character_lifes.lifes > 0 {
-                    character.set_speed(enemy.speed * 0.02);
-                    audio.play(loader.collision_sound.clone());
-                } else {
-                    add_explosion_events.send(AddExplosionEvent {
-                        position: character.position,
-                    });
+    for (character_entity, character, character_active) in character_query.iter() {
+        if !character_active.get_active() {
+            continue;
+        }
 
-                    commands.entity(character_entity).despawn();
+        let collider = character.get_collider();
+        let enemies = enemy_grid.query(&collider).into_iter().filter(|entity| {
+            enemy_query
+                .get(*entity)
+                .is_ok_and(|enemy| collider.intersects(&enemy.get_collider()))
+        });
+        let bosses = boss_query
+            .iter()
+            .filter(|(_, boss, health)| {
+                health.is_alive() && collider.intersects(&boss.get_collider())
+            })
+            .map(|(entity, _, _)| entity);
 
-                    audio.play(loader.explosion_sound.clone());
-                }
-            }
-        }
+        collision_events.send_batch(enemies.chain(bosses).map(|entity| CollisionEvent {
+            a: entity,
+            b: character_entity,
+            kind: CollisionKind::Ram,
+        }));
     }
 }
 
-fn check_bullet_collision(
-    bullet_query: Query<(Entity, &Bullet)>,
-    enemy_query: Query<(Entity, &Enemy)>,
-    mut enemy_count_query: Query<&mut EnemyCount>,
-    mut add_explosion_events: EventWriter<AddExplosionEvent>,
-    mut enemies_left_events: EventWriter<EnemiesLeftEvent>,
-    mut commands: Commands,
-    loader: ResMut<LoaderState>,
-    audio: Res<Audio>,
+fn check_enemy_bullet_collision(
+    character_query: Query<(Entity, &Character, &CharacterActive)>,
+    enemy_bullet_query: Query<(Entity, &EnemyBullet, Option<&PreviousPosition>)>,
+    mut collision_events: EventWriter<CollisionEvent>,
 ) {
-    for (bullet_entity, bullet) in bullet_query.iter() {
-        for (enemy_entity, enemy) in enemy_query.iter() {
-            if hit_test(bullet.get_bounding_rect(), enemy.get_bounding_rect()) {
-                add_explosion_events.send(AddExplosionEvent {
-                    position: enemy.position,
-                });
+    for (character_entity, character, character_active) in character_query.iter() {
+        if !character_active.get_active() {
+            continue;
+        }
 
-                let mut enemy_count = enemy_count_query.single_mut();
-                enemy_count.remove();
-                enemies_left_events.send(EnemiesLeftEvent {
-                    enemies_left: enemy_count.count,
+        let collider = character.get_collider();
+        for (enemy_bullet_entity, enemy_bullet, previous_position) in enemy_bullet_query.iter() {
+            let (start, motion) = path(enemy_bullet, previous_position);
+            if start.sweep(motion, &collider).is_some() {
+                collision_events.send(CollisionEvent {
+                    a: enemy_bullet_entity,
+                    b: character_entity,
+                    kind: CollisionKind::EnemyShot,
                 });
+            }
+        }
+    }
+}
 
-                commands.entity(enemy_entity).despawn();
-                commands.entity(bullet_entity).despawn();
+/// The character running into power-ups, active or not.
+fn check_power_up_collision(
+    character_query: Query<(Entity, &Character)>,
+    power_up_query: Query<(Entity, &PowerUp)>,
+    mut collision_events: EventWriter<CollisionEvent>,
+) {
+    for (character_entity, character) in character_query.iter() {
+        let collider = character.get_collider();
+        collision_events.send_batch(
+            power_up_query
+                .iter()
+                .filter(|(_, power_up)| collider.intersects(&power_up.get_collider()))
+                .map(|(power_up_entity, _)| CollisionEvent {
+                    a: power_up_entity,
+                    b: character_entity,
+                    kind: CollisionKind::Pickup,
+                }),
+        );
+    }
+}
 
-                audio.play(loader.explosion_sound.clone());
-            }
+/// Bullets hitting enemies and the boss, along the whole way they went this tick. Only
+/// the earliest hit of a bullet that stops at the first is reported.
+fn check_bullet_collision(
+    bullet_query: Query<(Entity, &Bullet, Option<&PreviousPosition>)>,
+    enemy_grid: Res<SpatialHash<Enemy>>,
+    enemy_query: Query<(&Enemy, &Health)>,
+    boss_query: Query<(Entity, &Boss, &Health)>,
+    mut collision_events: EventWriter<CollisionEvent>,
+) {
+    for (bullet_entity, bullet, previous_position) in bullet_query.iter() {
+        let (start, motion) = path(bullet, previous_position);
+        let enemies = enemy_grid
+            .query_area(start.swept_bounds(motion))
+            .into_iter()
+            .filter_map(|entity| {
+                let (enemy, health) = enemy_query.get(entity).ok()?;
+                Some((entity, enemy.get_collider(), health))
+            });
+        let bosses = boss_query
+            .iter()
+            .map(|(entity, boss, health)| (entity, boss.get_collider(), health));
+        let mut hits: Vec<(f32, Entity)> = enemies
+            .chain(bosses)
+            .filter(|(entity, _, health)| health.is_alive() && bullet.can_hit(*entity))
+            .filter_map(|(entity, collider, _)| {
+                let share = start.sweep(motion, &collider)?;
+                Some((share, entity))
+            })
+            .collect();
+        hits.sort_by(|lhs, rhs| lhs.0.total_cmp(&rhs.0));
+        if !bullet.weapon().pierces() {
+            hits.truncate(1);
         }
+
+        collision_events.send_batch(hits.into_iter().map(|(_, entity)| CollisionEvent {
+            a: bullet_entity,
+            b: entity,
+            kind: CollisionKind::Shot,
+        }));
     }
 }
 
+/// Finds what collides each tick and sends a `CollisionEvent` for it. Damage, score,
+/// sounds and explosions are left to the modules of the entities involved.
 pub struct CollisionPlugin;
 
 impl Plugin for CollisionPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<AddExplosionEvent>()
-            .add_system_set(
-                SystemSet::on_update(AppState::Main).with_system(check_character_collision),
+        app.init_resource::<SpatialHash<Enemy>>()
+            .add_system_set_to_stage(
+                FixedUpdateStage,
+                on_fixed_update(AppState::Main)
+                    .after(FixedUpdateLabel::Movement)
+                    .before(FixedUpdateLabel::Collision)
+                    .with_system(rebuild::<Enemy>),
             )
-            .add_system_set(
-                SystemSet::on_update(AppState::Main).with_system(check_bullet_collision),
+            .add_system_set_to_stage(
+                FixedUpdateStage,
+                on_fixed_update(AppState::Main)
+                    .label(FixedUpdateLabel::Collision)
+                    .after(FixedUpdateLabel::Movement)
+                    .with_system(check_character_collision)
+                    .with_system(check_enemy_bullet_collision)
+                    .with_system(check_bullet_collision)
+                    .with_system(check_power_up_collision),
             );
     }
 }
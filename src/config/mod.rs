@@ -0,0 +1,463 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+use std::fmt;
+
+pub static CONFIG_PATH: &str = "game.config.ron";
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct CharacterConfig {
+    pub lifes: u32,
+    /// Top speed in pixels per second.
+    pub max_speed: f32,
+    /// Share of the speed kept every tick.
+    pub friction: f32,
+    /// Seconds of invulnerability after a collision.
+    pub inactive_duration: f32,
+}
+
+impl Default for CharacterConfig {
+    fn default() -> Self {
+        Self {
+            lifes: 3,
+            max_speed: 150.0,
+            friction: 0.96,
+            inactive_duration: 3.0,
+        }
+    }
+}
+
+/// Base values of the single shot, the other weapons scale them.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct BulletConfig {
+    pub speed: f32,
+    pub initial_delay: f32,
+    pub delay: f32,
+    /// Health a projectile takes from whatever it hits.
+    pub damage: u32,
+}
+
+impl Default for BulletConfig {
+    fn default() -> Self {
+        Self {
+            speed: 300.0,
+            initial_delay: 0.1,
+            delay: 0.5,
+            damage: 10,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct EnemiesConfig {
+    pub min_speed: f32,
+    pub max_speed: f32,
+    /// Health of a drifter, tougher kinds scale it.
+    pub health: u32,
+}
+
+impl Default for EnemiesConfig {
+    fn default() -> Self {
+        Self {
+            min_speed: 100.0,
+            max_speed: 200.0,
+            health: 10,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct EnemyBulletConfig {
+    pub speed: f32,
+    /// Seconds between two volleys of a shooter.
+    pub cooldown: f32,
+    /// Radians between the shots of a spread.
+    pub spread_angle: f32,
+}
+
+impl Default for EnemyBulletConfig {
+    fn default() -> Self {
+        Self {
+            speed: 180.0,
+            cooldown: 2.0,
+            spread_angle: 0.3,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct WavesConfig {
+    pub first_enemy_count: u32,
+    pub enemy_increment: u32,
+    pub max_enemy_count: u32,
+    pub speed_increment: f32,
+    pub max_speed_scale: f32,
+    /// Seconds between a cleared wave and the next one.
+    pub delay: f32,
+    pub final_wave: u32,
+}
+
+impl Default for WavesConfig {
+    fn default() -> Self {
+        Self {
+            first_enemy_count: 5,
+            enemy_increment: 2,
+            max_enemy_count: 25,
+            speed_increment: 0.1,
+            max_speed_scale: 2.0,
+            delay: 2.0,
+            final_wave: 10,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct BossConfig {
+    /// The boss shows up once this wave is cleared, a wave past `waves.final_wave`
+    /// leaves it out.
+    pub wave: u32,
+    pub health: u32,
+    /// Seconds spent in each attack phase.
+    pub phase_duration: f32,
+    /// Bullets in one ring.
+    pub ring_bullets: u32,
+    /// Seconds between two rings.
+    pub ring_interval: f32,
+    pub charge_speed: f32,
+    /// Enemies spawned by one minion phase.
+    pub minion_count: u32,
+}
+
+impl Default for BossConfig {
+    fn default() -> Self {
+        Self {
+            wave: 10,
+            health: 400,
+            phase_duration: 5.0,
+            ring_bullets: 12,
+            ring_interval: 1.0,
+            charge_speed: 450.0,
+            minion_count: 3,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct PowerUpsConfig {
+    /// Chance of a destroyed enemy dropping a pickup.
+    pub drop_chance: f32,
+    pub drift_speed: f32,
+    /// Seconds before a pickup expires, it blinks during the last `blink_time` ones.
+    pub lifetime: f32,
+    pub blink_time: f32,
+    pub shield_duration: f32,
+    pub boost_duration: f32,
+    /// Cooldown multiplier while the fire rate is boosted.
+    pub boost_factor: f32,
+}
+
+impl Default for PowerUpsConfig {
+    fn default() -> Self {
+        Self {
+            drop_chance: 0.12,
+            drift_speed: 30.0,
+            lifetime: 8.0,
+            blink_time: 2.0,
+            shield_duration: 5.0,
+            boost_duration: 6.0,
+            boost_factor: 0.5,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct BombConfig {
+    /// Bombs available at the start of a run.
+    pub charges: u32,
+    /// Reach of a bomb around the character.
+    pub radius: f32,
+    /// Seconds of invulnerability after setting one off.
+    pub invulnerability: f32,
+}
+
+impl Default for BombConfig {
+    fn default() -> Self {
+        Self {
+            charges: 3,
+            radius: 250.0,
+            invulnerability: 1.5,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct RainConfig {
+    pub drop_count: u32,
+}
+
+impl Default for RainConfig {
+    fn default() -> Self {
+        Self { drop_count: 200 }
+    }
+}
+
+/// Gameplay tuning, loaded from `assets/game.config.ron`. Missing fields keep their
+/// defaults, debug builds pick up changes to the file while running.
+#[derive(Resource, Deserialize, TypeUuid, Clone, Debug, Default, PartialEq)]
+#[uuid = "7972efcb-1ad7-44ed-baaa-3b6983130c74"]
+#[serde(default)]
+pub struct GameConfig {
+    pub character: CharacterConfig,
+    pub bullet: BulletConfig,
+    pub enemies: EnemiesConfig,
+    pub enemy_bullet: EnemyBulletConfig,
+    pub waves: WavesConfig,
+    pub boss: BossConfig,
+    pub power_ups: PowerUpsConfig,
+    pub bomb: BombConfig,
+    pub rain: RainConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    pub field: &'static str,
+    pub message: &'static str,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}` {}", self.field, self.message)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+fn check(valid: bool, field: &'static str, message: &'static str) -> Result<(), ConfigError> {
+    if valid {
+        Ok(())
+    } else {
+        Err(ConfigError { field, message })
+    }
+}
+
+impl GameConfig {
+    pub fn from_ron(bytes: &[u8]) -> Result<Self, bevy::asset::Error> {
+        let config: GameConfig = ron::de::from_bytes(bytes)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Rejects values the game can't run with, e.g. a zero spawn count or a min speed
+    /// above the max one.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let GameConfig {
+            character,
+            bullet,
+            enemies,
+            enemy_bullet,
+            waves,
+            boss,
+            power_ups,
+            bomb,
+            rain,
+        } = self;
+
+        check(character.lifes > 0, "character.lifes", "must be positive")?;
+        check(
+            character.max_speed > 0.0,
+            "character.max_speed",
+            "must be positive",
+        )?;
+        check(
+            character.friction > 0.0 && character.friction <= 1.0,
+            "character.friction",
+            "must be within (0, 1]",
+        )?;
+        check(
+            character.inactive_duration >= 0.0,
+            "character.inactive_duration",
+            "must not be negative",
+        )?;
+        check(bullet.speed > 0.0, "bullet.speed", "must be positive")?;
+        check(
+            bullet.initial_delay >= 0.0,
+            "bullet.initial_delay",
+            "must not be negative",
+        )?;
+        check(bullet.delay >= 0.0, "bullet.delay", "must not be negative")?;
+        check(bullet.damage > 0, "bullet.damage", "must be positive")?;
+        check(
+            enemies.min_speed >= 0.0,
+            "enemies.min_speed",
+            "must not be negative",
+        )?;
+        check(
+            enemies.max_speed >= enemies.min_speed,
+            "enemies.max_speed",
+            "must not be below enemies.min_speed",
+        )?;
+        check(enemies.health > 0, "enemies.health", "must be positive")?;
+        check(
+            enemy_bullet.speed > 0.0,
+            "enemy_bullet.speed",
+            "must be positive",
+        )?;
+        check(
+            enemy_bullet.cooldown > 0.0,
+            "enemy_bullet.cooldown",
+            "must be positive",
+        )?;
+        check(
+            enemy_bullet.spread_angle >= 0.0,
+            "enemy_bullet.spread_angle",
+            "must not be negative",
+        )?;
+        check(
+            waves.first_enemy_count > 0,
+            "waves.first_enemy_count",
+            "must be positive",
+        )?;
+        check(
+            waves.max_enemy_count >= waves.first_enemy_count,
+            "waves.max_enemy_count",
+            "must not be below waves.first_enemy_count",
+        )?;
+        check(
+            waves.speed_increment >= 0.0,
+            "waves.speed_increment",
+            "must not be negative",
+        )?;
+        check(
+            waves.max_speed_scale >= 1.0,
+            "waves.max_speed_scale",
+            "must be at least 1",
+        )?;
+        check(waves.delay >= 0.0, "waves.delay", "must not be negative")?;
+        check(waves.final_wave > 0, "waves.final_wave", "must be positive")?;
+        check(boss.wave > 0, "boss.wave", "must be positive")?;
+        check(boss.health > 0, "boss.health", "must be positive")?;
+        check(
+            boss.phase_duration > 0.0,
+            "boss.phase_duration",
+            "must be positive",
+        )?;
+        check(
+            boss.ring_bullets > 0,
+            "boss.ring_bullets",
+            "must be positive",
+        )?;
+        check(
+            boss.ring_interval > 0.0,
+            "boss.ring_interval",
+            "must be positive",
+        )?;
+        check(
+            boss.charge_speed > 0.0,
+            "boss.charge_speed",
+            "must be positive",
+        )?;
+        check(
+            (0.0..=1.0).contains(&power_ups.drop_chance),
+            "power_ups.drop_chance",
+            "must be within [0, 1]",
+        )?;
+        check(
+            power_ups.drift_speed >= 0.0,
+            "power_ups.drift_speed",
+            "must not be negative",
+        )?;
+        check(
+            power_ups.lifetime > 0.0,
+            "power_ups.lifetime",
+            "must be positive",
+        )?;
+        check(
+            power_ups.blink_time >= 0.0 && power_ups.blink_time <= power_ups.lifetime,
+            "power_ups.blink_time",
+            "must be within [0, power_ups.lifetime]",
+        )?;
+        check(
+            power_ups.shield_duration >= 0.0,
+            "power_ups.shield_duration",
+            "must not be negative",
+        )?;
+        check(
+            power_ups.boost_duration >= 0.0,
+            "power_ups.boost_duration",
+            "must not be negative",
+        )?;
+        check(
+            power_ups.boost_factor > 0.0,
+            "power_ups.boost_factor",
+            "must be positive",
+        )?;
+        check(bomb.radius > 0.0, "bomb.radius", "must be positive")?;
+        check(
+            bomb.invulnerability >= 0.0,
+            "bomb.invulnerability",
+            "must not be negative",
+        )?;
+        check(rain.drop_count > 0, "rain.drop_count", "must be positive")
+    }
+}
+
+#[derive(Default)]
+pub struct GameConfigLoader;
+
+impl AssetLoader for GameConfigLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let config = GameConfig::from_ron(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(config));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["config.ron"]
+    }
+}
+
+/// Copies the loaded or hot-reloaded asset into the `GameConfig` resource.
+fn apply_config(
+    mut asset_events: EventReader<AssetEvent<GameConfig>>,
+    assets: Res<Assets<GameConfig>>,
+    mut config: ResMut<GameConfig>,
+) {
+    for asset_event in asset_events.iter() {
+        if let AssetEvent::Created { handle } | AssetEvent::Modified { handle } = asset_event {
+            if let Some(loaded) = assets.get(handle) {
+                *config = loaded.clone();
+                info!("Game config applied");
+            }
+        }
+    }
+}
+
+pub struct GameConfigPlugin;
+
+impl Plugin for GameConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<GameConfig>()
+            .init_asset_loader::<GameConfigLoader>()
+            .init_resource::<GameConfig>()
+            .add_system(apply_config);
+    }
+}
@@ -1,4 +1,4 @@
-use stars_rs::utils::Size;
+use crate::utils::Size;
 
 pub static WINDOW_SIZE: Size = Size {
     width: 600.0,
@@ -11,11 +11,17 @@ pub struct PositionZ {
     pub bullet: f32,
     pub character: f32,
     pub explosion: f32,
+    pub damage: f32,
     pub rain: f32,
     pub menu: f32,
     pub aim: f32,
+    pub touch: f32,
     pub enemies_left: f32,
     pub character_lifes: f32,
+    pub wave: f32,
+    pub boss_health: f32,
+    pub score: f32,
+    pub weapon: f32,
     pub fps: f32,
     pub loader: f32,
 }
@@ -25,11 +31,17 @@ pub static POSITION_Z: PositionZ = PositionZ {
     bullet: 2.0,
     character: 3.0,
     explosion: 4.0,
+    damage: 4.5,
     rain: 5.0,
     menu: 6.0,
     aim: 7.0,
+    touch: 7.5,
     enemies_left: 8.0,
     character_lifes: 9.0,
-    fps: 10.0,
-    loader: 11.0,
+    wave: 10.0,
+    boss_health: 11.0,
+    score: 12.0,
+    weapon: 13.0,
+    fps: 14.0,
+    loader: 15.0,
 };
@@ -0,0 +1,604 @@
+use crate::{
+    gamepad::{GamepadButtonsMut, GamepadInput},
+    menu::{button_bundle, button_text},
+    state::{despawn_with, AppState, LoaderState},
+    storage::Storage,
+    touch::TouchControls,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Name the keymap is stored as.
+pub static STORAGE_NAME: &str = "keymap";
+
+/// What the player can do, independent of the key or button that does it.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Fire,
+    Bomb,
+    NextWeapon,
+    PreviousWeapon,
+    Weapon1,
+    Weapon2,
+    Weapon3,
+    Weapon4,
+    Weapon5,
+    Pause,
+}
+
+impl Action {
+    /// Every action, in the order of the controls screen.
+    pub const ALL: [Action; 14] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Fire,
+        Action::Bomb,
+        Action::NextWeapon,
+        Action::PreviousWeapon,
+        Action::Weapon1,
+        Action::Weapon2,
+        Action::Weapon3,
+        Action::Weapon4,
+        Action::Weapon5,
+        Action::Pause,
+    ];
+
+    /// Actions selecting a weapon directly, in `WeaponKind::ALL` order.
+    pub const WEAPONS: [Action; 5] = [
+        Action::Weapon1,
+        Action::Weapon2,
+        Action::Weapon3,
+        Action::Weapon4,
+        Action::Weapon5,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move up",
+            Action::MoveDown => "Move down",
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::Fire => "Fire",
+            Action::Bomb => "Bomb",
+            Action::NextWeapon => "Next weapon",
+            Action::PreviousWeapon => "Previous weapon",
+            Action::Weapon1 => "Weapon 1",
+            Action::Weapon2 => "Weapon 2",
+            Action::Weapon3 => "Weapon 3",
+            Action::Weapon4 => "Weapon 4",
+            Action::Weapon5 => "Weapon 5",
+            Action::Pause => "Pause",
+        }
+    }
+
+    fn default_bindings(&self) -> Vec<Binding> {
+        let (keys, mouse_buttons, button_types): (
+            &[KeyCode],
+            &[MouseButton],
+            &[GamepadButtonType],
+        ) = match self {
+            Action::MoveUp => (
+                &[KeyCode::W, KeyCode::Up],
+                &[],
+                &[GamepadButtonType::DPadUp],
+            ),
+            Action::MoveDown => (
+                &[KeyCode::S, KeyCode::Down],
+                &[],
+                &[GamepadButtonType::DPadDown],
+            ),
+            Action::MoveLeft => (
+                &[KeyCode::A, KeyCode::Left],
+                &[],
+                &[GamepadButtonType::DPadLeft],
+            ),
+            Action::MoveRight => (
+                &[KeyCode::D, KeyCode::Right],
+                &[],
+                &[GamepadButtonType::DPadRight],
+            ),
+            Action::Fire => (
+                &[],
+                &[MouseButton::Left],
+                &[GamepadButtonType::RightTrigger2],
+            ),
+            Action::Bomb => (
+                &[KeyCode::Space],
+                &[MouseButton::Right],
+                &[GamepadButtonType::LeftTrigger2],
+            ),
+            Action::NextWeapon => (&[KeyCode::E], &[], &[GamepadButtonType::RightTrigger]),
+            Action::PreviousWeapon => (&[KeyCode::Q], &[], &[GamepadButtonType::LeftTrigger]),
+            Action::Weapon1 => (&[KeyCode::Key1], &[], &[]),
+            Action::Weapon2 => (&[KeyCode::Key2], &[], &[]),
+            Action::Weapon3 => (&[KeyCode::Key3], &[], &[]),
+            Action::Weapon4 => (&[KeyCode::Key4], &[], &[]),
+            Action::Weapon5 => (&[KeyCode::Key5], &[], &[]),
+            Action::Pause => (&[KeyCode::Escape], &[], &[GamepadButtonType::Start]),
+        };
+
+        keys.iter()
+            .copied()
+            .map(Binding::Key)
+            .chain(mouse_buttons.iter().copied().map(Binding::Mouse))
+            .chain(button_types.iter().copied().map(Binding::Gamepad))
+            .collect()
+    }
+}
+
+/// Kind of input a binding comes from. Keyboard and mouse share one column on the
+/// controls screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Device {
+    KeyboardMouse,
+    Gamepad,
+}
+
+/// Key or button bound to an action.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButtonType),
+}
+
+impl Binding {
+    pub fn device(&self) -> Device {
+        match self {
+            Binding::Key(_) | Binding::Mouse(_) => Device::KeyboardMouse,
+            Binding::Gamepad(_) => Device::Gamepad,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Binding::Key(key_code) => format!("{:?}", key_code),
+            Binding::Mouse(mouse_button) => format!("Mouse {:?}", mouse_button),
+            Binding::Gamepad(button_type) => format!("{:?}", button_type),
+        }
+    }
+}
+
+/// Bindings of every action. Actions missing from a stored keymap keep their defaults.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Keymap {
+    bindings: BTreeMap<Action, Vec<Binding>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            bindings: Action::ALL
+                .iter()
+                .map(|action| (*action, action.default_bindings()))
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    pub fn bindings(&self, action: Action) -> &[Binding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Makes `binding` the only binding of `action` for its device and takes it away
+    /// from any other action.
+    pub fn bind(&mut self, action: Action, binding: Binding) {
+        for bindings in self.bindings.values_mut() {
+            bindings.retain(|other| *other != binding);
+        }
+
+        let bindings = self.bindings.entry(action).or_default();
+        bindings.retain(|other| other.device() != binding.device());
+        bindings.push(binding);
+    }
+
+    fn fill_defaults(&mut self) {
+        for action in Action::ALL {
+            self.bindings
+                .entry(action)
+                .or_insert_with(|| action.default_bindings());
+        }
+    }
+}
+
+fn just_pressed(
+    action: Action,
+    keymap: &Keymap,
+    keyboard_input: &Input<KeyCode>,
+    mouse_button_input: &Input<MouseButton>,
+    gamepad_just_pressed: impl Fn(GamepadButtonType) -> bool,
+    touch: &TouchControls,
+) -> bool {
+    keymap.bindings(action).iter().any(|binding| match binding {
+        Binding::Key(key_code) => keyboard_input.just_pressed(*key_code),
+        Binding::Mouse(mouse_button) => mouse_button_input.just_pressed(*mouse_button),
+        Binding::Gamepad(button_type) => gamepad_just_pressed(*button_type),
+    }) || (action == Action::Fire && touch.fire_just_pressed())
+}
+
+/// Keyboard, mouse and gamepad read through the keymap, plus the touch controls.
+#[derive(SystemParam)]
+pub struct ActionInput<'w, 's> {
+    keymap: Res<'w, Keymap>,
+    keyboard_input: Res<'w, Input<KeyCode>>,
+    mouse_button_input: Res<'w, Input<MouseButton>>,
+    pub gamepad: GamepadInput<'w, 's>,
+    touch: Res<'w, TouchControls>,
+}
+
+impl ActionInput<'_, '_> {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.keymap
+            .bindings(action)
+            .iter()
+            .any(|binding| match binding {
+                Binding::Key(key_code) => self.keyboard_input.pressed(*key_code),
+                Binding::Mouse(mouse_button) => self.mouse_button_input.pressed(*mouse_button),
+                Binding::Gamepad(button_type) => self.gamepad.pressed(*button_type),
+            })
+            || (action == Action::Fire && self.touch.fire_pressed())
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        just_pressed(
+            action,
+            &self.keymap,
+            &self.keyboard_input,
+            &self.mouse_button_input,
+            |button_type| self.gamepad.just_pressed(button_type),
+            &self.touch,
+        )
+    }
+
+    /// Analog movement from the left stick of a gamepad or the touch move stick, at
+    /// most 1 long.
+    pub fn move_stick(&self) -> Vec2 {
+        let stick = self.gamepad.left_stick();
+        if stick != Vec2::ZERO {
+            return stick;
+        }
+
+        self.touch.move_stick()
+    }
+
+    /// Analog aim from the right stick of a gamepad or the touch aim stick, at most 1
+    /// long.
+    pub fn aim_stick(&self) -> Vec2 {
+        let stick = self.gamepad.right_stick();
+        if stick != Vec2::ZERO {
+            return stick;
+        }
+
+        self.touch.aim_stick()
+    }
+}
+
+/// Presses of actions for systems that consume them. Takes the inputs mutably, use
+/// `ActionInput` to only read them.
+#[derive(SystemParam)]
+pub struct ActionInputMut<'w, 's> {
+    keymap: Res<'w, Keymap>,
+    keyboard_input: ResMut<'w, Input<KeyCode>>,
+    mouse_button_input: ResMut<'w, Input<MouseButton>>,
+    gamepad: GamepadButtonsMut<'w, 's>,
+    touch: Res<'w, TouchControls>,
+}
+
+impl ActionInputMut<'_, '_> {
+    pub fn just_pressed(&self, action: Action) -> bool {
+        just_pressed(
+            action,
+            &self.keymap,
+            &self.keyboard_input,
+            &self.mouse_button_input,
+            |button_type| self.gamepad.just_pressed(button_type),
+            &self.touch,
+        )
+    }
+
+    /// Forgets a press of `action` so that systems running later in the frame don't
+    /// see it.
+    pub fn reset(&mut self, action: Action) {
+        for binding in self.keymap.bindings(action).to_vec() {
+            match binding {
+                Binding::Key(key_code) => self.keyboard_input.reset(key_code),
+                Binding::Mouse(mouse_button) => self.mouse_button_input.reset(mouse_button),
+                Binding::Gamepad(button_type) => self.gamepad.reset(button_type),
+            }
+        }
+    }
+}
+
+/// Action and device waiting for a new binding on the controls screen.
+#[derive(Resource, Default)]
+struct Rebinding(Option<(Action, Device)>);
+
+#[derive(Component)]
+struct ControlsScreen;
+
+/// Button of the controls screen showing the bindings of `action` for `device`,
+/// clicking it waits for a new one.
+#[derive(Component)]
+pub struct BindingSlot {
+    pub action: Action,
+    pub device: Device,
+}
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ControlsButton {
+    Reset,
+    Back,
+}
+
+impl ControlsButton {
+    fn label(&self) -> &'static str {
+        match self {
+            ControlsButton::Reset => "Reset",
+            ControlsButton::Back => "Back",
+        }
+    }
+}
+
+fn load_keymap(storage: Res<Storage>, mut keymap: ResMut<Keymap>) {
+    *keymap = storage.load(STORAGE_NAME);
+    keymap.fill_defaults();
+}
+
+fn setup(mut commands: Commands, loader: Res<LoaderState>, mut rebinding: ResMut<Rebinding>) {
+    rebinding.0 = None;
+
+    let style = TextStyle {
+        font: loader.font.clone(),
+        font_size: 20.0,
+        color: Color::WHITE,
+    };
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::NONE.into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Controls",
+                TextStyle {
+                    font_size: 40.0,
+                    color: Color::GOLD,
+                    ..style.clone()
+                },
+            ));
+
+            for action in Action::ALL {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: Color::NONE.into(),
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn(
+                            TextBundle::from_section(action.label(), style.clone()).with_style(
+                                Style {
+                                    size: Size::new(Val::Px(170.0), Val::Auto),
+                                    ..default()
+                                },
+                            ),
+                        );
+
+                        for device in [Device::KeyboardMouse, Device::Gamepad] {
+                            let mut button = button_bundle(190.0);
+                            button.style.size.height = Val::Px(22.0);
+                            button.style.margin = UiRect::all(Val::Px(1.0));
+
+                            parent
+                                .spawn(button)
+                                .with_children(|parent| {
+                                    parent.spawn(TextBundle::from_section("", style.clone()));
+                                })
+                                .insert(BindingSlot { action, device });
+                        }
+                    });
+            }
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        ..default()
+                    },
+                    background_color: Color::NONE.into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for controls_button in [ControlsButton::Reset, ControlsButton::Back] {
+                        let mut button = button_bundle(150.0);
+                        button.style.size.height = Val::Px(40.0);
+                        button.style.margin = UiRect::all(Val::Px(6.0));
+
+                        parent
+                            .spawn(button)
+                            .with_children(|parent| {
+                                parent.spawn(button_text(
+                                    controls_button.label(),
+                                    loader.font.clone(),
+                                ));
+                            })
+                            .insert(controls_button);
+                    }
+                });
+        })
+        .insert(ControlsScreen);
+}
+
+/// Starts waiting for a new binding when a slot is clicked.
+fn select_slot(
+    interaction_query: Query<(&Interaction, &BindingSlot), Changed<Interaction>>,
+    mut rebinding: ResMut<Rebinding>,
+    loader: Res<LoaderState>,
+    audio: Res<Audio>,
+) {
+    for (interaction, slot) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            rebinding.0 = Some((slot.action, slot.device));
+
+            audio.play(loader.button_sound.clone());
+        }
+    }
+}
+
+/// Binds the next key or button of the awaited device, Escape cancels. Runs before
+/// `select_slot` so that the click picking a slot is not taken as its binding, and
+/// mouse presses on any other button of the screen are left to that button.
+fn capture_binding(
+    mut rebinding: ResMut<Rebinding>,
+    mut keymap: ResMut<Keymap>,
+    mut keyboard_input: ResMut<Input<KeyCode>>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    gamepad: GamepadInput,
+    interaction_query: Query<&Interaction, With<Button>>,
+    storage: Res<Storage>,
+) {
+    let Some((action, device)) = rebinding.0 else {
+        return;
+    };
+
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        keyboard_input.reset(KeyCode::Escape);
+        rebinding.0 = None;
+        return;
+    }
+
+    let binding = match device {
+        Device::KeyboardMouse => keyboard_input
+            .get_just_pressed()
+            .next()
+            .map(|key_code| Binding::Key(*key_code))
+            .or_else(|| {
+                if interaction_query
+                    .iter()
+                    .any(|interaction| *interaction == Interaction::Clicked)
+                {
+                    return None;
+                }
+
+                mouse_button_input
+                    .get_just_pressed()
+                    .next()
+                    .map(|mouse_button| Binding::Mouse(*mouse_button))
+            }),
+        Device::Gamepad => gamepad.first_just_pressed().map(Binding::Gamepad),
+    };
+
+    if let Some(binding) = binding {
+        keymap.bind(action, binding);
+        storage.save(STORAGE_NAME, &*keymap);
+        rebinding.0 = None;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn controls_buttons(
+    interaction_query: Query<(&Interaction, &ControlsButton), Changed<Interaction>>,
+    mut keyboard_input: ResMut<Input<KeyCode>>,
+    mut keymap: ResMut<Keymap>,
+    mut rebinding: ResMut<Rebinding>,
+    mut state: ResMut<State<AppState>>,
+    storage: Res<Storage>,
+    loader: Res<LoaderState>,
+    audio: Res<Audio>,
+) {
+    for (interaction, controls_button) in interaction_query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+
+        match controls_button {
+            ControlsButton::Reset => {
+                *keymap = Keymap::default();
+                storage.save(STORAGE_NAME, &*keymap);
+                rebinding.0 = None;
+            }
+            ControlsButton::Back => state.set(AppState::Menu).unwrap(),
+        }
+
+        audio.play(loader.button_sound.clone());
+    }
+
+    if rebinding.0.is_none() && keyboard_input.just_pressed(KeyCode::Escape) {
+        keyboard_input.reset(KeyCode::Escape);
+        state.set(AppState::Menu).unwrap();
+    }
+}
+
+fn update_slots(
+    keymap: Res<Keymap>,
+    rebinding: Res<Rebinding>,
+    slot_query: Query<(&BindingSlot, &Children)>,
+    added_query: Query<(), Added<BindingSlot>>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !keymap.is_changed() && !rebinding.is_changed() && added_query.is_empty() {
+        return;
+    }
+
+    for (slot, children) in slot_query.iter() {
+        let value = if rebinding.0 == Some((slot.action, slot.device)) {
+            "Press...".to_string()
+        } else {
+            keymap
+                .bindings(slot.action)
+                .iter()
+                .filter(|binding| binding.device() == slot.device)
+                .map(Binding::label)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = value.clone();
+            }
+        }
+    }
+}
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Keymap>()
+            .init_resource::<Rebinding>()
+            .add_startup_system(load_keymap)
+            .add_system_set(SystemSet::on_enter(AppState::Controls).with_system(setup))
+            .add_system_set(
+                SystemSet::on_update(AppState::Controls)
+                    .with_system(capture_binding.before(select_slot))
+                    .with_system(select_slot)
+                    .with_system(controls_buttons.after(capture_binding))
+                    .with_system(update_slots.after(select_slot).after(controls_buttons)),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Controls).with_system(despawn_with::<ControlsScreen>),
+            );
+    }
+}
@@ -1,14 +1,27 @@
 use crate::utils;
 use crate::{
+    character::Character,
+    config::{EnemiesConfig, GameConfig},
     consts::{POSITION_Z, WINDOW_SIZE},
-    events::EnemiesLeftEvent,
-    state::{AppState, LoaderState},
-    utils::{random_in_range, random_in_rect_edge, BoundingRect, GetBoundingRect, Position},
+    enemy_bullet::EnemyGun,
+    events::{DamageEvent, EnemiesLeftEvent},
+    health::{Health, HitFlash},
+    power_ups::drop_power_up,
+    rng::GameRng,
+    state::{despawn_with, AppState, LoaderState},
+    timestep::{
+        interpolate, on_fixed_update, FixedUpdateLabel, FixedUpdateStage, PreviousPosition,
+        TIMESTEP,
+    },
+    utils::{
+        random_in_range, random_in_rect_edge, steer, BoundingRect, Collider, GetBoundingRect,
+        GetPosition, Position,
+    },
 };
 use bevy::prelude::*;
+use rand::Rng;
+use std::f32::consts::FRAC_PI_4;
 
-static MIN_SPEED: f32 = 100.0;
-static MAX_SPEED: f32 = 200.0;
 pub static ENEMY_SIZE: utils::Size = utils::Size {
     width: 128.0,
     height: 128.0,
@@ -17,10 +30,119 @@ static ENEMY_EDGE_POSITION: Position = Position {
     x: (WINDOW_SIZE.width + ENEMY_SIZE.width) / 2.0,
     y: (WINDOW_SIZE.height + ENEMY_SIZE.height) / 2.0,
 };
-static ENEMY_COUNT: u32 = 5;
+
+static CHASER_TURN_RATE: f32 = 1.5;
+static WEAVER_AMPLITUDE: f32 = 80.0;
+static WEAVER_FREQUENCY: f32 = 3.0;
+static SHARD_COUNT: u32 = 2;
 
 #[derive(Component, Deref, DerefMut)]
-struct EnemyAnimationTimer(Timer);
+pub struct EnemyAnimationTimer(pub Timer);
+
+/// Archetype of an enemy, decides its look, movement, toughness and score value.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnemyKind {
+    /// Flies in a straight line.
+    Drifter,
+    /// Flies in a straight line while weaving from side to side.
+    Weaver,
+    /// Steers toward the character.
+    Chaser,
+    /// Breaks into shards when destroyed.
+    Splitter,
+    /// Slow, takes several hits.
+    Tank,
+    /// Drifts slowly and fires at the character.
+    Shooter,
+    /// Small and fast, left behind by a splitter.
+    Shard,
+}
+
+impl EnemyKind {
+    /// Kinds waves pick from, with the first wave each one shows up in.
+    const UNLOCKS: [(EnemyKind, u32); 6] = [
+        (EnemyKind::Drifter, 1),
+        (EnemyKind::Weaver, 2),
+        (EnemyKind::Chaser, 3),
+        (EnemyKind::Splitter, 4),
+        (EnemyKind::Tank, 5),
+        (EnemyKind::Shooter, 6),
+    ];
+
+    /// Picks one of the kinds unlocked by `wave`.
+    pub fn random<R: Rng + ?Sized>(rng: &mut R, wave: u32) -> Self {
+        let unlocked: Vec<EnemyKind> = Self::UNLOCKS
+            .iter()
+            .filter(|(_, unlock_wave)| *unlock_wave <= wave)
+            .map(|(kind, _)| *kind)
+            .collect();
+
+        if unlocked.is_empty() {
+            EnemyKind::Drifter
+        } else {
+            unlocked[rng.gen_range(0..unlocked.len())]
+        }
+    }
+
+    /// Size relative to `ENEMY_SIZE`.
+    pub fn scale(&self) -> f32 {
+        match self {
+            EnemyKind::Drifter | EnemyKind::Splitter => 1.0,
+            EnemyKind::Weaver | EnemyKind::Shooter => 0.85,
+            EnemyKind::Chaser => 0.75,
+            EnemyKind::Tank => 1.4,
+            EnemyKind::Shard => 0.5,
+        }
+    }
+
+    /// Factor applied to the configured enemy speed range.
+    pub fn speed_factor(&self) -> f32 {
+        match self {
+            EnemyKind::Drifter => 1.0,
+            EnemyKind::Weaver => 0.9,
+            EnemyKind::Chaser => 0.8,
+            EnemyKind::Splitter => 0.7,
+            EnemyKind::Tank | EnemyKind::Shooter => 0.5,
+            EnemyKind::Shard => 1.3,
+        }
+    }
+
+    pub fn tint(&self) -> Color {
+        match self {
+            EnemyKind::Drifter => Color::WHITE,
+            EnemyKind::Weaver => Color::LIME_GREEN,
+            EnemyKind::Chaser => Color::TOMATO,
+            EnemyKind::Splitter => Color::ORANGE,
+            EnemyKind::Tank => Color::VIOLET,
+            EnemyKind::Shooter => Color::CYAN,
+            EnemyKind::Shard => Color::YELLOW,
+        }
+    }
+
+    /// Points for destroying the enemy, before the combo multiplier.
+    pub fn score(&self) -> u32 {
+        match self {
+            EnemyKind::Drifter => 100,
+            EnemyKind::Weaver | EnemyKind::Chaser => 150,
+            EnemyKind::Splitter | EnemyKind::Shooter => 200,
+            EnemyKind::Tank => 300,
+            EnemyKind::Shard => 50,
+        }
+    }
+
+    /// Health, relative to `enemies.health`.
+    pub fn health(&self, config: &EnemiesConfig) -> u32 {
+        let factor = match self {
+            EnemyKind::Tank => 3,
+            _ => 1,
+        };
+
+        config.health * factor
+    }
+}
+
+#[derive(Resource, Deref)]
+pub struct EnemyTextureAtlas(Handle<TextureAtlas>);
 
 #[derive(Component, Deref, DerefMut)]
 pub struct EnemyCount {
@@ -29,7 +151,7 @@ pub struct EnemyCount {
 
 impl EnemyCount {
     pub fn remove(&mut self) {
-        self.count = self.count - 1;
+        self.count -= 1;
     }
 }
 
@@ -37,20 +159,31 @@ impl EnemyCount {
 pub struct Enemy {
     pub position: Vec2,
     pub speed: Vec2,
+    speed_scale: f32,
+    scale: f32,
+    age: f32,
 }
 
 impl Enemy {
-    fn new(position: Vec2, speed: Vec2) -> Self {
+    fn new(kind: EnemyKind, position: Vec2, speed: Vec2, speed_scale: f32) -> Self {
         Self {
-            position: position,
-            speed: speed,
+            position,
+            speed,
+            speed_scale,
+            scale: kind.scale(),
+            age: 0.0,
         }
     }
 }
 
 impl Default for Enemy {
     fn default() -> Self {
-        Self::new(Vec2::new(0.0, 0.0), Vec2::new(0.0, 0.0))
+        Self::new(
+            EnemyKind::Drifter,
+            Vec2::new(0.0, 0.0),
+            Vec2::new(0.0, 0.0),
+            1.0,
+        )
     }
 }
 
@@ -59,14 +192,29 @@ impl GetBoundingRect for Enemy {
         BoundingRect {
             x: self.position.x,
             y: self.position.y,
-            width: ENEMY_SIZE.width,
-            height: ENEMY_SIZE.height,
+            width: ENEMY_SIZE.width * self.scale,
+            height: ENEMY_SIZE.height * self.scale,
+        }
+    }
+
+    fn get_collider(&self) -> Collider {
+        Collider::OrientedBox {
+            center: self.position,
+            half_size: Vec2::new(ENEMY_SIZE.width, ENEMY_SIZE.height) * self.scale / 4.0,
+            rotation: get_rotation_z(&self.speed),
         }
     }
 }
 
-fn get_position() -> Vec2 {
+impl GetPosition for Enemy {
+    fn get_position(&self) -> Vec2 {
+        self.position
+    }
+}
+
+fn get_position(rng: &mut GameRng) -> Vec2 {
     random_in_rect_edge(
+        rng,
         -ENEMY_EDGE_POSITION.x,
         ENEMY_EDGE_POSITION.x,
         ENEMY_EDGE_POSITION.y,
@@ -74,45 +222,56 @@ fn get_position() -> Vec2 {
     )
 }
 
-fn get_speed(position: &Vec2) -> Vec2 {
-    if position.x == -ENEMY_EDGE_POSITION.x {
+fn get_speed(
+    rng: &mut GameRng,
+    config: &EnemiesConfig,
+    kind: EnemyKind,
+    position: &Vec2,
+    speed_scale: f32,
+) -> Vec2 {
+    let min_speed = config.min_speed * kind.speed_factor();
+    let max_speed = config.max_speed * kind.speed_factor();
+
+    let speed = if position.x == -ENEMY_EDGE_POSITION.x {
         Vec2::new(
-            random_in_range(MIN_SPEED, MAX_SPEED),
-            random_in_range(-MAX_SPEED, MAX_SPEED),
+            random_in_range(rng, min_speed, max_speed),
+            random_in_range(rng, -max_speed, max_speed),
         )
     } else if position.x == ENEMY_EDGE_POSITION.x {
         Vec2::new(
-            random_in_range(-MAX_SPEED, -MIN_SPEED),
-            random_in_range(-MAX_SPEED, MAX_SPEED),
+            random_in_range(rng, -max_speed, -min_speed),
+            random_in_range(rng, -max_speed, max_speed),
         )
     } else if position.y == ENEMY_EDGE_POSITION.y {
         Vec2::new(
-            random_in_range(-MAX_SPEED, MAX_SPEED),
-            random_in_range(-MAX_SPEED, -MIN_SPEED),
+            random_in_range(rng, -max_speed, max_speed),
+            random_in_range(rng, -max_speed, -min_speed),
         )
     } else {
         Vec2::new(
-            random_in_range(-MAX_SPEED, MAX_SPEED),
-            random_in_range(MIN_SPEED, MAX_SPEED),
+            random_in_range(rng, -max_speed, max_speed),
+            random_in_range(rng, min_speed, max_speed),
         )
-    }
+    };
+
+    speed * speed_scale
 }
 
 fn get_rotation_z(speed: &Vec2) -> f32 {
     -speed.x.atan2(speed.y)
 }
 
-fn add_enemy_count(mut commands: Commands, mut enemies_left_events: EventWriter<EnemiesLeftEvent>) {
-    let count = ENEMY_COUNT;
-
-    commands.spawn_empty().insert(EnemyCount { count });
+fn add_enemy_count(mut commands: Commands) {
+    commands.spawn_empty().insert(EnemyCount { count: 0 });
+}
 
-    enemies_left_events.send(EnemiesLeftEvent {
-        enemies_left: count,
-    });
+fn reset_enemy_count(mut query: Query<&mut EnemyCount>) {
+    for mut enemy_count in query.iter_mut() {
+        enemy_count.count = 0;
+    }
 }
 
-fn add_enemies(
+fn add_texture_atlas(
     mut commands: Commands,
     loader: Res<LoaderState>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
@@ -127,28 +286,104 @@ fn add_enemies(
         Option::None,
     );
 
-    let texture_atlas_handle = texture_atlases.add(texture_atlas);
+    commands.insert_resource(EnemyTextureAtlas(texture_atlases.add(texture_atlas)));
+}
+
+fn add_enemy(
+    commands: &mut Commands,
+    texture_atlas: &EnemyTextureAtlas,
+    config: &EnemiesConfig,
+    kind: EnemyKind,
+    position: Vec2,
+    speed: Vec2,
+    speed_scale: f32,
+) {
+    let rotation_z = get_rotation_z(&speed);
+
+    let mut entity = commands.spawn(SpriteSheetBundle {
+        sprite: TextureAtlasSprite {
+            color: kind.tint(),
+            custom_size: Some(Vec2::new(ENEMY_SIZE.width, ENEMY_SIZE.height) * kind.scale()),
+            ..default()
+        },
+        texture_atlas: texture_atlas.0.clone(),
+        transform: Transform::from_xyz(position.x, position.y, POSITION_Z.enemy)
+            .with_rotation(Quat::from_rotation_z(rotation_z)),
+        ..default()
+    });
+
+    entity
+        .insert(EnemyAnimationTimer(Timer::from_seconds(
+            0.07,
+            TimerMode::Repeating,
+        )))
+        .insert(PreviousPosition(position))
+        .insert(Enemy::new(kind, position, speed, speed_scale))
+        .insert(Health::new(kind.health(config)))
+        .insert(HitFlash::new(kind.tint()))
+        .insert(kind);
 
-    for _ in 0..ENEMY_COUNT {
-        let position = get_position();
-        let speed = get_speed(&position);
-        let rotation_z = get_rotation_z(&speed);
+    if kind == EnemyKind::Shooter {
+        entity.insert(EnemyGun::default());
+    }
+}
+
+/// Spawns `count` enemies of the kinds unlocked by `wave` on the screen edges, with
+/// speeds scaled by `speed_scale`.
+pub fn add_enemies(
+    commands: &mut Commands,
+    texture_atlas: &EnemyTextureAtlas,
+    rng: &mut GameRng,
+    config: &EnemiesConfig,
+    wave: u32,
+    count: u32,
+    speed_scale: f32,
+) {
+    for _ in 0..count {
+        let kind = EnemyKind::random(rng, wave);
+        let position = get_position(rng);
+        let speed = get_speed(rng, config, kind, &position, speed_scale);
 
-        commands
-            .spawn(SpriteSheetBundle {
-                texture_atlas: texture_atlas_handle.clone(),
-                transform: Transform::from_xyz(position.x, position.y, POSITION_Z.enemy)
-                    .with_rotation(Quat::from_rotation_z(rotation_z)),
-                ..default()
-            })
-            .insert(EnemyAnimationTimer(Timer::from_seconds(
-                0.07,
-                TimerMode::Repeating,
-            )))
-            .insert(Enemy::new(position, speed));
+        add_enemy(
+            commands,
+            texture_atlas,
+            config,
+            kind,
+            position,
+            speed,
+            speed_scale,
+        );
     }
 }
 
+/// Spawns the shards a destroyed splitter breaks into, fanning out from its heading.
+/// Returns how many were spawned.
+fn add_shards(
+    commands: &mut Commands,
+    texture_atlas: &EnemyTextureAtlas,
+    config: &EnemiesConfig,
+    enemy: &Enemy,
+) -> u32 {
+    let speed = enemy.speed * EnemyKind::Shard.speed_factor() / EnemyKind::Splitter.speed_factor();
+
+    for index in 0..SHARD_COUNT {
+        let angle = FRAC_PI_4 * (2.0 * index as f32 / (SHARD_COUNT - 1) as f32 - 1.0);
+        let shard_speed = Vec2::from_angle(angle).rotate(speed);
+
+        add_enemy(
+            commands,
+            texture_atlas,
+            config,
+            EnemyKind::Shard,
+            enemy.position,
+            shard_speed,
+            enemy.speed_scale,
+        );
+    }
+
+    SHARD_COUNT
+}
+
 fn animate(
     time: Res<Time>,
     texture_atlases: Res<Assets<TextureAtlas>>,
@@ -166,38 +401,118 @@ fn animate(
     }
 }
 
-fn update_enemies(time: Res<Time>, mut query: Query<(&mut Enemy, &mut Transform)>) {
-    let delta_seconds = time.delta_seconds();
+fn update_enemies(
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    character_query: Query<&Character>,
+    mut query: Query<(
+        &mut Enemy,
+        &EnemyKind,
+        &mut PreviousPosition,
+        &mut Transform,
+    )>,
+) {
+    let delta_seconds = TIMESTEP as f32;
+    let target = character_query
+        .get_single()
+        .ok()
+        .map(|character| character.position);
 
-    for (mut enemy, mut transform) in query.iter_mut() {
-        if transform.translation.x > ENEMY_EDGE_POSITION.x
-            || transform.translation.x < -ENEMY_EDGE_POSITION.x
-            || transform.translation.y > ENEMY_EDGE_POSITION.y
-            || transform.translation.y < -ENEMY_EDGE_POSITION.y
+    for (mut enemy, kind, mut previous_position, mut transform) in query.iter_mut() {
+        if enemy.position.x > ENEMY_EDGE_POSITION.x
+            || enemy.position.x < -ENEMY_EDGE_POSITION.x
+            || enemy.position.y > ENEMY_EDGE_POSITION.y
+            || enemy.position.y < -ENEMY_EDGE_POSITION.y
         {
-            let position = get_position();
-            let speed = get_speed(&position);
+            let position = get_position(&mut rng);
+            let speed = get_speed(
+                &mut rng,
+                &config.enemies,
+                *kind,
+                &position,
+                enemy.speed_scale,
+            );
             let rotation_z = get_rotation_z(&speed);
 
             enemy.position.x = position.x;
             enemy.position.y = position.y;
             enemy.speed.x = speed.x;
             enemy.speed.y = speed.y;
+            enemy.age = 0.0;
 
-            transform.translation.x = position.x;
-            transform.translation.y = position.y;
+            previous_position.0 = position;
             transform.rotation = Quat::from_rotation_z(rotation_z);
         } else {
+            previous_position.0 = enemy.position;
+            enemy.age += delta_seconds;
+
+            match (kind, target) {
+                (EnemyKind::Chaser, Some(target)) => {
+                    enemy.speed = steer(
+                        enemy.position,
+                        enemy.speed,
+                        target,
+                        CHASER_TURN_RATE * delta_seconds,
+                    );
+                    transform.rotation = Quat::from_rotation_z(get_rotation_z(&enemy.speed));
+                }
+                (EnemyKind::Weaver, _) => {
+                    let side = enemy.speed.perp().normalize_or_zero();
+                    let sway =
+                        WEAVER_AMPLITUDE * WEAVER_FREQUENCY * (WEAVER_FREQUENCY * enemy.age).cos();
+
+                    enemy.position += side * sway * delta_seconds;
+                }
+                _ => {}
+            }
+
             enemy.position.x += enemy.speed.x * delta_seconds;
             enemy.position.y += enemy.speed.y * delta_seconds;
         }
     }
 }
 
-fn position_changed(mut query: Query<(&Enemy, &mut Transform), Changed<Enemy>>) {
-    for (enemy, mut transform) in query.iter_mut() {
-        transform.translation.x = enemy.position.x;
-        transform.translation.y = enemy.position.y;
+/// Removes the enemies killed this tick. Splitters break into shards, and a power-up
+/// may drop where an enemy was.
+#[allow(clippy::too_many_arguments)]
+fn remove_killed(
+    mut damage_events: EventReader<DamageEvent>,
+    enemy_query: Query<(&Enemy, &EnemyKind)>,
+    mut enemy_count_query: Query<&mut EnemyCount>,
+    mut enemies_left_events: EventWriter<EnemiesLeftEvent>,
+    mut rng: ResMut<GameRng>,
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    texture_atlas: Res<EnemyTextureAtlas>,
+    loader: Res<LoaderState>,
+) {
+    for damage_event in damage_events.iter() {
+        if !damage_event.fatal {
+            continue;
+        }
+
+        let Ok((enemy, kind)) = enemy_query.get(damage_event.entity) else {
+            continue;
+        };
+
+        let mut enemy_count = enemy_count_query.single_mut();
+        enemy_count.remove();
+        if *kind == EnemyKind::Splitter {
+            enemy_count.count += add_shards(&mut commands, &texture_atlas, &config.enemies, enemy);
+        }
+        enemies_left_events.send(EnemiesLeftEvent {
+            enemies_left: enemy_count.count,
+        });
+
+        drop_power_up(
+            &mut commands,
+            &mut rng,
+            &loader,
+            &config.power_ups,
+            enemy.position,
+        );
+
+        commands.entity(damage_event.entity).despawn();
     }
 }
 
@@ -206,9 +521,25 @@ pub struct EnemiesPlugin;
 impl Plugin for EnemiesPlugin {
     fn build(&self, app: &mut App) {
         app.add_startup_system(add_enemy_count)
-            .add_system_set(SystemSet::on_enter(AppState::Main).with_system(add_enemies))
+            .add_system_set(SystemSet::on_exit(AppState::Loading).with_system(add_texture_atlas))
             .add_system_set(SystemSet::on_update(AppState::Main).with_system(animate))
-            .add_system_set(SystemSet::on_update(AppState::Main).with_system(update_enemies))
-            .add_system_set(SystemSet::on_update(AppState::Main).with_system(position_changed));
+            .add_system_set(SystemSet::on_update(AppState::Main).with_system(interpolate::<Enemy>))
+            .add_system_set_to_stage(
+                FixedUpdateStage,
+                on_fixed_update(AppState::Main)
+                    .label(FixedUpdateLabel::Movement)
+                    .with_system(update_enemies),
+            )
+            .add_system_set_to_stage(
+                FixedUpdateStage,
+                on_fixed_update(AppState::Main)
+                    .after(FixedUpdateLabel::Damage)
+                    .with_system(remove_killed),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Main)
+                    .with_system(despawn_with::<Enemy>)
+                    .with_system(reset_enemy_count),
+            );
     }
 }
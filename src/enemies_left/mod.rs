@@ -2,7 +2,7 @@ use crate::{
     consts::{POSITION_Z, WINDOW_SIZE},
     enemies::EnemyCount,
     events::EnemiesLeftEvent,
-    state::{AppState, LoaderState},
+    state::{despawn_with, AppState, LoaderState},
 };
 use bevy::prelude::*;
 
@@ -71,6 +71,9 @@ impl Plugin for EnemiesLeftTextPlugin {
         app.add_system_set(SystemSet::on_enter(AppState::Main).with_system(add_enemies_left_text))
             .add_system_set(
                 SystemSet::on_update(AppState::Main).with_system(update_enemies_left_text),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Main).with_system(despawn_with::<EnemiesLeftText>),
             );
     }
 }
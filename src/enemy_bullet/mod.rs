@@ -0,0 +1,197 @@
+use crate::utils;
+use crate::{
+    character::Character,
+    config::GameConfig,
+    consts::{POSITION_Z, WINDOW_SIZE},
+    enemies::Enemy,
+    events::CharacterHitEvent,
+    state::{despawn_with, AppState},
+    timestep::{
+        interpolate, on_fixed_update, FixedUpdateLabel, FixedUpdateStage, PreviousPosition,
+        TIMESTEP,
+    },
+    utils::{BoundingRect, Collider, GetBoundingRect, GetPosition},
+};
+use bevy::{prelude::*, utils::Duration};
+use std::f32::consts::FRAC_PI_4;
+
+static ENEMY_BULLET_SIZE: utils::Size = utils::Size {
+    width: 10.0,
+    height: 10.0,
+};
+const ENEMY_BULLET_COLOR: Color = Color::ORANGE_RED;
+static SPREAD_SHOTS: i32 = 1;
+
+/// Cooldown of a shooting enemy, volleys alternate between an aimed shot and a spread.
+#[derive(Component, Default)]
+pub struct EnemyGun {
+    timer: Timer,
+    spread: bool,
+}
+
+/// Projectile fired by an enemy, only hurts the character.
+#[derive(Component)]
+pub struct EnemyBullet {
+    position: Vec2,
+    speed: Vec2,
+}
+
+impl EnemyBullet {
+    pub fn new(position: Vec2, speed: Vec2) -> Self {
+        Self { position, speed }
+    }
+
+    pub fn speed(&self) -> Vec2 {
+        self.speed
+    }
+}
+
+impl GetBoundingRect for EnemyBullet {
+    fn get_bounding_rect(&self) -> BoundingRect {
+        BoundingRect {
+            x: self.position.x,
+            y: self.position.y,
+            width: ENEMY_BULLET_SIZE.width,
+            height: ENEMY_BULLET_SIZE.height,
+        }
+    }
+
+    /// The diamond the sprite is drawn as, at half the size like every other collider.
+    fn get_collider(&self) -> Collider {
+        Collider::OrientedBox {
+            center: self.position,
+            half_size: Vec2::new(ENEMY_BULLET_SIZE.width, ENEMY_BULLET_SIZE.height) / 4.0,
+            rotation: FRAC_PI_4,
+        }
+    }
+}
+
+impl GetPosition for EnemyBullet {
+    fn get_position(&self) -> Vec2 {
+        self.position
+    }
+}
+
+pub fn add_enemy_bullet(commands: &mut Commands, position: Vec2, speed: Vec2) {
+    commands
+        .spawn(SpriteBundle {
+            sprite: Sprite {
+                color: ENEMY_BULLET_COLOR,
+                custom_size: Some(Vec2::new(ENEMY_BULLET_SIZE.width, ENEMY_BULLET_SIZE.height)),
+                ..default()
+            },
+            transform: Transform::from_xyz(position.x, position.y, POSITION_Z.bullet)
+                .with_rotation(Quat::from_rotation_z(FRAC_PI_4)),
+            ..default()
+        })
+        .insert(PreviousPosition(position))
+        .insert(EnemyBullet::new(position, speed));
+}
+
+fn is_on_screen(position: Vec2) -> bool {
+    position.x.abs() < WINDOW_SIZE.width / 2.0 && position.y.abs() < WINDOW_SIZE.height / 2.0
+}
+
+fn fire_enemy_guns(
+    config: Res<GameConfig>,
+    character_query: Query<&Character>,
+    mut query: Query<(&Enemy, &mut EnemyGun)>,
+    mut commands: Commands,
+) {
+    let Ok(character) = character_query.get_single() else {
+        return;
+    };
+    let cooldown = Duration::from_secs_f32(config.enemy_bullet.cooldown);
+
+    for (enemy, mut gun) in query.iter_mut() {
+        if gun.timer.duration() != cooldown {
+            gun.timer.set_duration(cooldown);
+            gun.timer.reset();
+        }
+
+        if !is_on_screen(enemy.position)
+            || !gun.timer.tick(Duration::from_secs_f64(TIMESTEP)).finished()
+        {
+            continue;
+        }
+
+        let aim = (character.position - enemy.position).normalize_or_zero();
+        let speed = aim * config.enemy_bullet.speed;
+
+        if gun.spread {
+            for shot in -SPREAD_SHOTS..=SPREAD_SHOTS {
+                let angle = config.enemy_bullet.spread_angle * shot as f32;
+                add_enemy_bullet(
+                    &mut commands,
+                    enemy.position,
+                    Vec2::from_angle(angle).rotate(speed),
+                );
+            }
+        } else {
+            add_enemy_bullet(&mut commands, enemy.position, speed);
+        }
+
+        gun.spread = !gun.spread;
+        gun.timer.reset();
+    }
+}
+
+fn update_enemy_bullets(
+    mut query: Query<(Entity, &mut EnemyBullet, &mut PreviousPosition)>,
+    mut commands: Commands,
+) {
+    let delta_seconds = TIMESTEP as f32;
+
+    for (entity, mut enemy_bullet, mut previous_position) in query.iter_mut() {
+        if enemy_bullet.position.x.abs() > (WINDOW_SIZE.width + ENEMY_BULLET_SIZE.width) / 2.0
+            || enemy_bullet.position.y.abs() > (WINDOW_SIZE.height + ENEMY_BULLET_SIZE.height) / 2.0
+        {
+            commands.entity(entity).despawn();
+        } else {
+            previous_position.0 = enemy_bullet.position;
+
+            enemy_bullet.position.x += enemy_bullet.speed.x * delta_seconds;
+            enemy_bullet.position.y += enemy_bullet.speed.y * delta_seconds;
+        }
+    }
+}
+
+/// Enemy bullets are gone once they hit. Bullets reaching the character while it's
+/// invulnerable fly on.
+fn despawn_hits(
+    mut character_hit_events: EventReader<CharacterHitEvent>,
+    query: Query<(), With<EnemyBullet>>,
+    mut commands: Commands,
+) {
+    for character_hit_event in character_hit_events.iter() {
+        if query.contains(character_hit_event.hitter) {
+            commands.entity(character_hit_event.hitter).despawn();
+        }
+    }
+}
+
+pub struct EnemyBulletPlugin;
+
+impl Plugin for EnemyBulletPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(AppState::Main).with_system(interpolate::<EnemyBullet>),
+        )
+        .add_system_set_to_stage(
+            FixedUpdateStage,
+            on_fixed_update(AppState::Main)
+                .label(FixedUpdateLabel::Movement)
+                .with_system(fire_enemy_guns)
+                .with_system(update_enemy_bullets),
+        )
+        .add_system_set_to_stage(
+            FixedUpdateStage,
+            on_fixed_update(AppState::Main)
+                .after(FixedUpdateLabel::Damage)
+                .with_system(despawn_hits),
+        )
+        .add_system_set(
+            SystemSet::on_exit(AppState::Main).with_system(despawn_with::<EnemyBullet>),
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::weapon::WeaponKind;
 use bevy::prelude::*;
 
 pub struct TransformEvent {
@@ -12,10 +13,74 @@ pub struct AddExplosionEvent {
 pub struct EnemiesLeftEvent {
     pub enemies_left: u32,
 }
+
+pub struct WaveEvent {
+    pub wave: u32,
+}
+
+pub struct ScoreEvent {
+    pub score: u32,
+    pub multiplier: u32,
+}
+
 pub struct CharacterLifesEvent {
     pub character_lifes: u32,
 }
 
+pub struct BombChargesEvent {
+    pub charges: u32,
+}
+
+/// Destroys the enemies and enemy bullets within `radius` of `position`. Updated by
+/// every gameplay tick instead of every frame.
+pub struct BombEvent {
+    pub position: Vec2,
+    pub radius: f32,
+}
+
+/// How the entities of a [`CollisionEvent`] met.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CollisionKind {
+    /// A bullet of the character, `a`, hit an enemy or the boss, `b`.
+    Shot,
+    /// An enemy bullet, `a`, hit the character, `b`.
+    EnemyShot,
+    /// An enemy or the boss, `a`, ran into the character, `b`.
+    Ram,
+    /// The character, `b`, picked up a power-up, `a`.
+    Pickup,
+}
+
+/// `a` and `b` collided this tick. Collision systems only report it, what comes of it
+/// is up to the systems reading these.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CollisionEvent {
+    pub a: Entity,
+    pub b: Entity,
+    pub kind: CollisionKind,
+}
+
+/// Something with `Health` at `position` was hit for `damage`, `fatal` when it died of
+/// it.
+pub struct DamageEvent {
+    pub entity: Entity,
+    pub position: Vec2,
+    pub damage: u32,
+    pub fatal: bool,
+}
+
+/// The character at `position` lost a life to a hit by `hitter`, `fatal` when it was
+/// the last.
+pub struct CharacterHitEvent {
+    pub hitter: Entity,
+    pub position: Vec2,
+    pub fatal: bool,
+}
+
+pub struct WeaponEvent {
+    pub weapon: WeaponKind,
+}
+
 pub struct EventsPlugin;
 
 impl Plugin for EventsPlugin {
@@ -23,6 +88,14 @@ impl Plugin for EventsPlugin {
         app.add_event::<TransformEvent>()
             .add_event::<AddExplosionEvent>()
             .add_event::<EnemiesLeftEvent>()
-            .add_event::<CharacterLifesEvent>();
+            .add_event::<WaveEvent>()
+            .add_event::<ScoreEvent>()
+            .add_event::<CharacterLifesEvent>()
+            .add_event::<WeaponEvent>()
+            .init_resource::<Events<BombEvent>>()
+            .add_event::<BombChargesEvent>()
+            .add_event::<CollisionEvent>()
+            .add_event::<DamageEvent>()
+            .add_event::<CharacterHitEvent>();
     }
 }
@@ -1,7 +1,8 @@
 use crate::{
+    boss::Boss,
     consts::POSITION_Z,
-    events::AddExplosionEvent,
-    state::{AppState, LoaderState},
+    events::{AddExplosionEvent, CharacterHitEvent, DamageEvent},
+    state::{despawn_with, AppState, LoaderState},
     utils,
 };
 use bevy::prelude::*;
@@ -23,6 +24,28 @@ impl Default for Explosion {
     }
 }
 
+/// Blows up whatever a hit killed, except the boss which goes down in a blast chain of
+/// its own.
+fn explode_kills(
+    mut damage_events: EventReader<DamageEvent>,
+    mut character_hit_events: EventReader<CharacterHitEvent>,
+    boss_query: Query<(), With<Boss>>,
+    mut add_explosion_events: EventWriter<AddExplosionEvent>,
+) {
+    let kills = damage_events
+        .iter()
+        .filter(|damage_event| damage_event.fatal && !boss_query.contains(damage_event.entity))
+        .map(|damage_event| damage_event.position)
+        .chain(
+            character_hit_events
+                .iter()
+                .filter(|character_hit_event| character_hit_event.fatal)
+                .map(|character_hit_event| character_hit_event.position),
+        );
+
+    add_explosion_events.send_batch(kills.map(|position| AddExplosionEvent { position }));
+}
+
 fn add_explosion(
     mut add_explosion_events: EventReader<AddExplosionEvent>,
     mut commands: Commands,
@@ -54,7 +77,7 @@ fn add_explosion(
                 0.08,
                 TimerMode::Repeating,
             )))
-            .insert(Explosion::default());
+            .insert(Explosion);
     }
 }
 
@@ -74,7 +97,7 @@ fn animate(
             let texture_atlas = texture_atlases.get(texture_atlas_handle).unwrap();
 
             if sprite.index < texture_atlas.len() - 1 {
-                sprite.index = sprite.index + 1;
+                sprite.index += 1;
             } else {
                 timer.reset();
                 commands.entity(entity).despawn();
@@ -87,7 +110,12 @@ pub struct ExplosionPlugin;
 
 impl Plugin for ExplosionPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system_set(SystemSet::on_update(AppState::Main).with_system(add_explosion))
-            .add_system_set(SystemSet::on_update(AppState::Main).with_system(animate));
+        app.add_system_set(
+            SystemSet::on_update(AppState::Main)
+                .with_system(explode_kills.before(add_explosion))
+                .with_system(add_explosion),
+        )
+        .add_system_set(SystemSet::on_update(AppState::Main).with_system(animate))
+        .add_system_set(SystemSet::on_exit(AppState::Main).with_system(despawn_with::<Explosion>));
     }
 }
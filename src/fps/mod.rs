@@ -62,7 +62,7 @@ pub struct FpsTextPlugin;
 
 impl Plugin for FpsTextPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugin(FrameTimeDiagnosticsPlugin::default())
+        app.add_plugin(FrameTimeDiagnosticsPlugin)
             .add_system_set(SystemSet::on_exit(AppState::Loading).with_system(add_fps_text))
             .add_system_set(SystemSet::on_update(AppState::Menu).with_system(update_fps_text))
             .add_system_set(SystemSet::on_update(AppState::Main).with_system(update_fps_text));
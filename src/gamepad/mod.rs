@@ -0,0 +1,208 @@
+use crate::menu::{HOVERED_BUTTON, NORMAL_BUTTON};
+use bevy::{ecs::system::SystemParam, input::InputSystem, prelude::*, ui::UiSystem};
+use std::marker::PhantomData;
+
+/// Stick deflection below this is drift and ignored.
+static STICK_DEADZONE: f32 = 0.2;
+
+fn just_pressed(
+    gamepads: &Gamepads,
+    buttons: &Input<GamepadButton>,
+    button_type: GamepadButtonType,
+) -> bool {
+    gamepads
+        .iter()
+        .any(|gamepad| buttons.just_pressed(GamepadButton::new(gamepad, button_type)))
+}
+
+/// Buttons and sticks of all connected gamepads, read as if they were one.
+#[derive(SystemParam)]
+pub struct GamepadInput<'w, 's> {
+    gamepads: Res<'w, Gamepads>,
+    buttons: Res<'w, Input<GamepadButton>>,
+    axes: Res<'w, Axis<GamepadAxis>>,
+    #[system_param(ignore)]
+    marker: PhantomData<&'s ()>,
+}
+
+impl GamepadInput<'_, '_> {
+    pub fn pressed(&self, button_type: GamepadButtonType) -> bool {
+        self.gamepads.iter().any(|gamepad| {
+            self.buttons
+                .pressed(GamepadButton::new(gamepad, button_type))
+        })
+    }
+
+    pub fn just_pressed(&self, button_type: GamepadButtonType) -> bool {
+        just_pressed(&self.gamepads, &self.buttons, button_type)
+    }
+
+    /// A button of a connected gamepad pressed this frame, if any.
+    pub fn first_just_pressed(&self) -> Option<GamepadButtonType> {
+        self.buttons
+            .get_just_pressed()
+            .find(|button| self.gamepads.contains(button.gamepad))
+            .map(|button| button.button_type)
+    }
+
+    /// Deflection of the left stick, at most 1 long.
+    pub fn left_stick(&self) -> Vec2 {
+        self.stick(GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY)
+    }
+
+    /// Deflection of the right stick, at most 1 long.
+    pub fn right_stick(&self) -> Vec2 {
+        self.stick(GamepadAxisType::RightStickX, GamepadAxisType::RightStickY)
+    }
+
+    fn stick(&self, x: GamepadAxisType, y: GamepadAxisType) -> Vec2 {
+        self.gamepads
+            .iter()
+            .map(|gamepad| {
+                Vec2::new(
+                    self.axes.get(GamepadAxis::new(gamepad, x)).unwrap_or(0.0),
+                    self.axes.get(GamepadAxis::new(gamepad, y)).unwrap_or(0.0),
+                )
+            })
+            .find(|stick| stick.length() > STICK_DEADZONE)
+            .map_or(Vec2::ZERO, |stick| stick.clamp_length_max(1.0))
+    }
+}
+
+/// Buttons of all connected gamepads for systems that consume presses. Takes the
+/// buttons mutably, use `GamepadInput` to only read them.
+#[derive(SystemParam)]
+pub struct GamepadButtonsMut<'w, 's> {
+    gamepads: Res<'w, Gamepads>,
+    buttons: ResMut<'w, Input<GamepadButton>>,
+    #[system_param(ignore)]
+    marker: PhantomData<&'s ()>,
+}
+
+impl GamepadButtonsMut<'_, '_> {
+    pub fn just_pressed(&self, button_type: GamepadButtonType) -> bool {
+        just_pressed(&self.gamepads, &self.buttons, button_type)
+    }
+
+    /// Forgets a press so that systems running later in the frame don't see it.
+    pub fn reset(&mut self, button_type: GamepadButtonType) {
+        for gamepad in self.gamepads.iter() {
+            self.buttons.reset(GamepadButton::new(gamepad, button_type));
+        }
+    }
+}
+
+/// Button picked with the D-pad, and the one last pressed with A.
+#[derive(Resource, Default)]
+struct MenuFocus {
+    focused: Option<Entity>,
+    pressed: Option<Entity>,
+}
+
+/// Indices of `entity` and its ancestors among their siblings, root first. Sorting by
+/// it gives the order a screen lists its nodes in.
+fn tree_position(
+    entity: Entity,
+    parent_query: &Query<&Parent>,
+    children_query: &Query<&Children>,
+) -> Vec<usize> {
+    let mut position = Vec::new();
+    let mut entity = entity;
+
+    while let Ok(parent) = parent_query.get(entity) {
+        let index = children_query
+            .get(parent.get())
+            .ok()
+            .and_then(|children| children.iter().position(|child| *child == entity))
+            .unwrap_or(0);
+        position.push(index);
+        entity = parent.get();
+    }
+
+    position.reverse();
+    position
+}
+
+/// Moves the focus between the buttons on screen with the D-pad, in the order their
+/// screen lists them.
+fn navigate_buttons(
+    gamepad: GamepadInput,
+    mut focus: ResMut<MenuFocus>,
+    mut button_query: Query<(Entity, &mut BackgroundColor), With<Button>>,
+    parent_query: Query<&Parent>,
+    children_query: Query<&Children>,
+) {
+    let step = if gamepad.just_pressed(GamepadButtonType::DPadDown) {
+        1
+    } else if gamepad.just_pressed(GamepadButtonType::DPadUp) {
+        -1
+    } else {
+        return;
+    };
+
+    let mut buttons: Vec<Entity> = button_query.iter().map(|(entity, _)| entity).collect();
+    if buttons.is_empty() {
+        return;
+    }
+    buttons.sort_by_cached_key(|entity| tree_position(*entity, &parent_query, &children_query));
+
+    let index = focus
+        .focused
+        .and_then(|focused| buttons.iter().position(|entity| *entity == focused))
+        .map_or(0, |index| {
+            (index as i32 + step).rem_euclid(buttons.len() as i32) as usize
+        });
+    focus.focused = Some(buttons[index]);
+
+    for (entity, mut color) in button_query.iter_mut() {
+        *color = if focus.focused == Some(entity) {
+            HOVERED_BUTTON.into()
+        } else {
+            NORMAL_BUTTON.into()
+        };
+    }
+}
+
+/// Clicks the focused button on A. Runs right after the UI has handled the mouse so
+/// that button systems see the click this frame.
+fn press_focused_button(
+    gamepad: GamepadInput,
+    mut focus: ResMut<MenuFocus>,
+    mut interaction_query: Query<&mut Interaction, With<Button>>,
+) {
+    if let Some(pressed) = focus.pressed.take() {
+        if let Ok(mut interaction) = interaction_query.get_mut(pressed) {
+            if *interaction == Interaction::Clicked {
+                *interaction = Interaction::None;
+            }
+        }
+    }
+
+    if !gamepad.just_pressed(GamepadButtonType::South) {
+        return;
+    }
+
+    let Some(focused) = focus.focused else {
+        return;
+    };
+
+    if let Ok(mut interaction) = interaction_query.get_mut(focused) {
+        *interaction = Interaction::Clicked;
+        focus.pressed = Some(focused);
+    }
+}
+
+pub struct GamepadPlugin;
+
+impl Plugin for GamepadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MenuFocus>()
+            .add_system(navigate_buttons)
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                press_focused_button
+                    .after(InputSystem)
+                    .after(UiSystem::Focus),
+            );
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{
+    state::{AppState, LoaderState},
+    storage::Storage,
+    timestep::TIMESTEP,
+};
+use bevy::{
+    asset::AssetPlugin,
+    diagnostic::DiagnosticsPlugin,
+    input::InputPlugin,
+    prelude::*,
+    time::TimeUpdateStrategy,
+    utils::{Duration, Instant},
+    window::WindowPlugin,
+};
+
+fn skip_loading(mut state: ResMut<State<AppState>>) {
+    state.set(AppState::Main).unwrap();
+}
+
+fn step_time(time: Res<Time>, mut time_update_strategy: ResMut<TimeUpdateStrategy>) {
+    let last_update = time.last_update().unwrap_or_else(|| time.startup());
+    let step = Duration::from_nanos((TIMESTEP * 1e9).ceil() as u64);
+
+    *time_update_strategy = TimeUpdateStrategy::ManualInstant(last_update + step);
+}
+
+/// Runs the shooter without a window, GPU or audio device.
+///
+/// Meant to be added on top of `MinimalPlugins` in place of `DefaultPlugins`, with
+/// `LoaderPlugin` disabled: assets are replaced by placeholder handles, player data is
+/// kept in memory only and the game goes straight from `Loading` to `Main`. Every
+/// `App::update` advances the clock by exactly one gameplay tick.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use stars_rs::{headless::HeadlessPlugin, loader::LoaderPlugin, StarsPluginGroup};
+///
+/// fn main() {
+///     let mut app = App::new();
+///     app.add_plugins(MinimalPlugins)
+///         .add_plugin(HeadlessPlugin)
+///         .add_plugins(StarsPluginGroup.build().disable::<LoaderPlugin>());
+///     app.update();
+/// }
+/// ```
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(DiagnosticsPlugin)
+            .add_plugin(InputPlugin)
+            .add_plugin(WindowPlugin {
+                add_primary_window: false,
+                exit_on_all_closed: false,
+                ..default()
+            })
+            .add_plugin(AssetPlugin::default())
+            .add_asset::<Image>()
+            .add_asset::<TextureAtlas>()
+            .add_asset::<AudioSource>()
+            .add_asset::<Font>()
+            .init_resource::<Audio>()
+            .insert_resource(TimeUpdateStrategy::ManualInstant(Instant::now()))
+            .insert_resource(LoaderState::default())
+            .insert_resource(Storage::memory())
+            .add_system_to_stage(CoreStage::Last, step_time)
+            .add_system_set(SystemSet::on_update(AppState::Loading).with_system(skip_loading));
+    }
+}
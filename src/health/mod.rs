@@ -0,0 +1,160 @@
+use crate::{
+    consts::POSITION_Z,
+    events::DamageEvent,
+    state::{despawn_with, AppState, LoaderState},
+};
+use bevy::prelude::*;
+
+static FLASH_DURATION: f32 = 0.08;
+const FLASH_COLOR: Color = Color::rgb(1.0, 0.2, 0.2);
+static DAMAGE_NUMBER_DURATION: f32 = 0.6;
+/// Pixels per second damage numbers float upwards.
+static DAMAGE_NUMBER_RISE: f32 = 60.0;
+
+/// Hit points of something that can be shot, it dies when `current` reaches zero.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Health {
+    pub current: u32,
+    pub max: u32,
+}
+
+impl Health {
+    pub fn new(max: u32) -> Self {
+        Self { current: max, max }
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.current > 0
+    }
+
+    /// Takes `damage` off the current health, returns whether it was the killing blow.
+    pub fn take(&mut self, damage: Damage) -> bool {
+        let was_alive = self.is_alive();
+        self.current = self.current.saturating_sub(damage.0);
+
+        was_alive && !self.is_alive()
+    }
+
+    pub fn kill(&mut self) {
+        self.current = 0;
+    }
+
+    /// Share of the health left, from 0 to 1.
+    pub fn fraction(&self) -> f32 {
+        if self.max == 0 {
+            return 0.0;
+        }
+
+        self.current as f32 / self.max as f32
+    }
+}
+
+/// Health a projectile takes from whatever it hits.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Deref)]
+pub struct Damage(pub u32);
+
+/// Tints the sprite for a moment after a hit, then restores `color`.
+#[derive(Component)]
+pub struct HitFlash {
+    color: Color,
+    timer: Timer,
+}
+
+impl HitFlash {
+    pub fn new(color: Color) -> Self {
+        let mut timer = Timer::from_seconds(FLASH_DURATION, TimerMode::Once);
+        timer.set_elapsed(timer.duration());
+
+        Self { color, timer }
+    }
+
+    pub fn start(&mut self) {
+        self.timer.reset();
+    }
+
+    pub fn is_flashing(&self) -> bool {
+        !self.timer.finished()
+    }
+}
+
+#[derive(Component, Deref, DerefMut)]
+struct DamageNumber(Timer);
+
+fn show_damage(
+    mut damage_events: EventReader<DamageEvent>,
+    mut flash_query: Query<&mut HitFlash>,
+    mut commands: Commands,
+    loader: Res<LoaderState>,
+) {
+    for damage_event in damage_events.iter() {
+        if let Ok(mut hit_flash) = flash_query.get_mut(damage_event.entity) {
+            hit_flash.start();
+        }
+
+        commands
+            .spawn(Text2dBundle {
+                text: Text::from_section(
+                    damage_event.damage.to_string(),
+                    TextStyle {
+                        font: loader.font.clone(),
+                        font_size: 14.0,
+                        color: Color::WHITE,
+                    },
+                )
+                .with_alignment(TextAlignment::CENTER),
+                transform: Transform::from_xyz(
+                    damage_event.position.x,
+                    damage_event.position.y,
+                    POSITION_Z.damage,
+                ),
+                ..default()
+            })
+            .insert(DamageNumber(Timer::from_seconds(
+                DAMAGE_NUMBER_DURATION,
+                TimerMode::Once,
+            )));
+    }
+}
+
+fn update_hit_flashes(time: Res<Time>, mut query: Query<(&mut HitFlash, &mut TextureAtlasSprite)>) {
+    for (mut hit_flash, mut sprite) in query.iter_mut() {
+        hit_flash.timer.tick(time.delta());
+
+        sprite.color = if hit_flash.is_flashing() {
+            FLASH_COLOR
+        } else {
+            hit_flash.color
+        };
+    }
+}
+
+fn update_damage_numbers(
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut DamageNumber, &mut Text, &mut Transform)>,
+    mut commands: Commands,
+) {
+    for (entity, mut timer, mut text, mut transform) in query.iter_mut() {
+        if timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        } else {
+            transform.translation.y += DAMAGE_NUMBER_RISE * time.delta_seconds();
+            text.sections[0].style.color.set_a(timer.percent_left());
+        }
+    }
+}
+
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(AppState::Main)
+                .with_system(show_damage)
+                .with_system(update_hit_flashes.after(show_damage))
+                .with_system(update_damage_numbers),
+        )
+        .add_system_set(
+            SystemSet::on_exit(AppState::Main).with_system(despawn_with::<DamageNumber>),
+        );
+    }
+}
@@ -0,0 +1,287 @@
+use crate::{
+    menu::{button_bundle, button_text},
+    score::Score,
+    state::{despawn_with, AppState, LoaderState},
+    stats::Stats,
+    storage::Storage,
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub static MAX_HIGH_SCORES: usize = 10;
+pub static MAX_NAME_LENGTH: usize = 12;
+static DEFAULT_NAME: &str = "Player";
+/// Name the table is stored as.
+pub static STORAGE_NAME: &str = "high_scores";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct HighScoreEntry {
+    pub name: String,
+    pub score: u32,
+    pub wave: u32,
+    pub date: String,
+}
+
+/// Best runs, highest score first, at most `MAX_HIGH_SCORES` entries.
+#[derive(Resource, Serialize, Deserialize, Default, Debug, PartialEq, Eq)]
+pub struct HighScores {
+    pub entries: Vec<HighScoreEntry>,
+}
+
+impl HighScores {
+    /// Whether `score` would make it into the table.
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0
+            && (self.entries.len() < MAX_HIGH_SCORES
+                || self.entries.last().is_none_or(|entry| score > entry.score))
+    }
+
+    /// Inserts `entry` below every entry with the same or a higher score and returns
+    /// its rank, `None` when it does not qualify.
+    pub fn insert(&mut self, entry: HighScoreEntry) -> Option<usize> {
+        if !self.qualifies(entry.score) {
+            return None;
+        }
+
+        let rank = self
+            .entries
+            .iter()
+            .position(|other| entry.score > other.score)
+            .unwrap_or(self.entries.len());
+
+        self.entries.insert(rank, entry);
+        self.entries.truncate(MAX_HIGH_SCORES);
+
+        Some(rank)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn unix_seconds() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn unix_seconds() -> u64 {
+    (js_sys::Date::now() / 1000.0) as u64
+}
+
+/// Formats a unix timestamp as a `YYYY-MM-DD` UTC date.
+pub fn format_date(unix_seconds: u64) -> String {
+    // Civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let days = (unix_seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Name typed in on the results screen when the run sets a new record.
+#[derive(Component, Default)]
+pub struct NameEntry {
+    pub name: String,
+}
+
+#[derive(Component)]
+struct HighScoresScreen;
+
+#[derive(Component)]
+struct BackButton;
+
+fn load_high_scores(storage: Res<Storage>, mut high_scores: ResMut<HighScores>) {
+    *high_scores = storage.load(STORAGE_NAME);
+}
+
+fn submit(
+    name: &str,
+    score: &Score,
+    stats: &Stats,
+    high_scores: &mut HighScores,
+    storage: &Storage,
+) -> String {
+    let name = match name.trim() {
+        "" => DEFAULT_NAME,
+        name => name,
+    };
+    let entry = HighScoreEntry {
+        name: name.to_string(),
+        score: score.points,
+        wave: stats.wave,
+        date: format_date(unix_seconds()),
+    };
+
+    if high_scores.insert(entry).is_some() {
+        storage.save(STORAGE_NAME, high_scores);
+    }
+
+    name.to_string()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn enter_name(
+    mut received_character_events: EventReader<ReceivedCharacter>,
+    mut keyboard_input: ResMut<Input<KeyCode>>,
+    mut query: Query<(Entity, &mut NameEntry, &mut Text)>,
+    score: Res<Score>,
+    stats: Res<Stats>,
+    mut high_scores: ResMut<HighScores>,
+    storage: Res<Storage>,
+    mut commands: Commands,
+) {
+    for (entity, mut name_entry, mut text) in query.iter_mut() {
+        for event in received_character_events.iter() {
+            if (event.char.is_alphanumeric() || event.char == ' ')
+                && name_entry.name.chars().count() < MAX_NAME_LENGTH
+            {
+                name_entry.name.push(event.char);
+            }
+        }
+
+        if keyboard_input.just_pressed(KeyCode::Back) {
+            name_entry.name.pop();
+        }
+
+        if keyboard_input.just_pressed(KeyCode::Return) {
+            keyboard_input.reset(KeyCode::Return);
+            text.sections[0].value = "Saved as ".to_string();
+            text.sections[1].value =
+                submit(&name_entry.name, &score, &stats, &mut high_scores, &storage);
+            commands.entity(entity).remove::<NameEntry>();
+        } else {
+            text.sections[1].value = format!("{}_", name_entry.name);
+        }
+    }
+}
+
+/// Keeps the record when the results screen is left without pressing Enter.
+fn submit_pending(
+    query: Query<&NameEntry>,
+    score: Res<Score>,
+    stats: Res<Stats>,
+    mut high_scores: ResMut<HighScores>,
+    storage: Res<Storage>,
+) {
+    for name_entry in query.iter() {
+        submit(&name_entry.name, &score, &stats, &mut high_scores, &storage);
+    }
+}
+
+fn setup(mut commands: Commands, loader: Res<LoaderState>, high_scores: Res<HighScores>) {
+    let style = TextStyle {
+        font: loader.font.clone(),
+        font_size: 24.0,
+        color: Color::WHITE,
+    };
+
+    let lines = if high_scores.entries.is_empty() {
+        vec!["No scores yet".to_string()]
+    } else {
+        high_scores
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                format!(
+                    "{:>2}. {:<12} {:>7}  Wave {:>2}  {}",
+                    index + 1,
+                    entry.name,
+                    entry.score,
+                    entry.wave,
+                    entry.date
+                )
+            })
+            .collect()
+    };
+
+    let mut button = button_bundle(150.0);
+    button.style.margin = UiRect::all(Val::Px(20.0));
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::NONE.into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "High scores",
+                TextStyle {
+                    font_size: 40.0,
+                    color: Color::GOLD,
+                    ..style.clone()
+                },
+            ));
+
+            for line in lines {
+                parent.spawn(TextBundle::from_section(line, style.clone()));
+            }
+
+            parent
+                .spawn(button)
+                .with_children(|parent| {
+                    parent.spawn(button_text("Back", loader.font.clone()));
+                })
+                .insert(BackButton);
+        })
+        .insert(HighScoresScreen);
+}
+
+fn back(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    mut keyboard_input: ResMut<Input<KeyCode>>,
+    mut state: ResMut<State<AppState>>,
+    loader: Res<LoaderState>,
+    audio: Res<Audio>,
+) {
+    let clicked = interaction_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+
+    if clicked {
+        audio.play(loader.button_sound.clone());
+    }
+
+    if clicked || keyboard_input.just_pressed(KeyCode::Escape) {
+        keyboard_input.reset(KeyCode::Escape);
+        state.set(AppState::Menu).unwrap();
+    }
+}
+
+pub struct HighScoresPlugin;
+
+impl Plugin for HighScoresPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HighScores>()
+            .add_startup_system(load_high_scores)
+            .add_system_set(SystemSet::on_enter(AppState::HighScores).with_system(setup))
+            .add_system_set(SystemSet::on_update(AppState::HighScores).with_system(back))
+            .add_system_set(
+                SystemSet::on_exit(AppState::HighScores)
+                    .with_system(despawn_with::<HighScoresScreen>),
+            );
+
+        for state in [AppState::GameOver, AppState::Victory] {
+            app.add_system_set(SystemSet::on_update(state.clone()).with_system(enter_name))
+                .add_system_set(SystemSet::on_exit(state).with_system(submit_pending));
+        }
+    }
+}
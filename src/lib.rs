@@ -1 +1,113 @@
+pub mod aim;
+pub mod app;
+pub mod background;
+pub mod bomb;
+pub mod boss;
+pub mod bullet;
+pub mod camera;
+pub mod character;
+pub mod character_lifes;
+pub mod cli;
+pub mod collision;
+pub mod config;
+pub mod controls;
+pub mod enemies;
+pub mod enemies_left;
+pub mod enemy_bullet;
+pub mod events;
+pub mod explosion;
+pub mod fps;
+pub mod gamepad;
+pub mod headless;
+pub mod health;
+pub mod high_scores;
+pub mod loader;
+pub mod menu;
+pub mod pause;
+pub mod power_ups;
+pub mod rain;
+pub mod replay;
+pub mod results;
+pub mod rng;
+pub mod score;
+pub mod score_text;
+pub mod sound;
+pub mod spatial_hash;
+pub mod state;
+pub mod stats;
+pub mod storage;
+pub mod timestep;
+pub mod touch;
+pub mod wave_text;
+pub mod waves;
+pub mod weapon;
+pub mod weapon_text;
+pub mod window;
+
+pub mod consts;
 pub mod utils;
+
+use bevy::{app::PluginGroupBuilder, prelude::*};
+
+/// All gameplay plugins of the shooter. Windowing, rendering and audio are left
+/// to the host, so the group is meant to be added after `DefaultPlugins`.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use stars_rs::{fps::FpsTextPlugin, StarsPluginGroup};
+///
+/// fn main() {
+///     App::new()
+///         .add_plugins(DefaultPlugins)
+///         .add_plugins(StarsPluginGroup.build().disable::<FpsTextPlugin>())
+///         .run();
+/// }
+/// ```
+pub struct StarsPluginGroup;
+
+impl PluginGroup for StarsPluginGroup {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(app::AppPlugin)
+            .add(events::EventsPlugin)
+            .add(config::GameConfigPlugin)
+            .add(storage::StoragePlugin)
+            .add(controls::ControlsPlugin)
+            .add(rng::RngPlugin::default())
+            .add(replay::ReplayPlugin::default())
+            .add(camera::CameraPlugin)
+            .add(state::StatePlugin)
+            .add(timestep::TimestepPlugin)
+            .add(loader::LoaderPlugin)
+            .add(menu::MenuPlugin)
+            .add(stats::StatsPlugin)
+            .add(score::ScorePlugin)
+            .add(high_scores::HighScoresPlugin)
+            .add(results::ResultsPlugin)
+            .add(pause::PausePlugin)
+            .add(background::BackgroundPlugin)
+            .add(bullet::BulletPlugin)
+            .add(weapon::WeaponPlugin)
+            .add(enemies::EnemiesPlugin)
+            .add(enemy_bullet::EnemyBulletPlugin)
+            .add(boss::BossPlugin)
+            .add(waves::WavesPlugin)
+            .add(character::CharacterPlugin)
+            .add(explosion::ExplosionPlugin)
+            .add(sound::SoundPlugin)
+            .add(collision::CollisionPlugin)
+            .add(health::HealthPlugin)
+            .add(power_ups::PowerUpsPlugin)
+            .add(bomb::BombPlugin)
+            .add(rain::RainPlugin)
+            .add(aim::AimPlugin)
+            .add(gamepad::GamepadPlugin)
+            .add(touch::TouchPlugin)
+            .add(fps::FpsTextPlugin)
+            .add(enemies_left::EnemiesLeftTextPlugin)
+            .add(character_lifes::CharacterLifesTextPlugin)
+            .add(score_text::ScoreTextPlugin)
+            .add(weapon_text::WeaponTextPlugin)
+            .add(wave_text::WaveTextPlugin)
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{
+    config::CONFIG_PATH,
     consts::POSITION_Z,
     state::{AppState, LoaderState},
 };
@@ -31,6 +32,7 @@ fn load(mut commands: Commands, asset_server: Res<AssetServer>) {
     let collision_sound: Handle<AudioSource> = asset_server.load("collision.ogg");
     let explosion_sound: Handle<AudioSource> = asset_server.load("explosion.ogg");
     let font: Handle<Font> = asset_server.load("FiraMono-Medium.ttf");
+    let config = asset_server.load(CONFIG_PATH);
 
     commands.insert_resource(LoaderState {
         background_image,
@@ -43,6 +45,7 @@ fn load(mut commands: Commands, asset_server: Res<AssetServer>) {
         collision_sound,
         explosion_sound,
         font,
+        config,
     });
 }
 
@@ -53,14 +56,23 @@ fn loading(
     time: Res<Time>,
     mut query: Query<&mut Transform, With<LoaderSprite>>,
 ) {
+    // A config that fails to load or validate is reported by the asset server, the
+    // game then runs with the defaults.
+    let config_load_state = asset_server.get_load_state(&loader.config);
+    let config_done = matches!(config_load_state, LoadState::Loaded | LoadState::Failed);
+
     match asset_server.get_group_load_state(loader.ids()) {
-        LoadState::Loading => {
+        LoadState::Loaded if config_done => {
+            if config_load_state == LoadState::Failed {
+                warn!("Using default game config");
+            }
+
+            state.set(AppState::Menu).unwrap();
+        }
+        LoadState::Loading | LoadState::Loaded => {
             let mut transform = query.single_mut();
             transform.rotation = Quat::from_rotation_z(time.elapsed_seconds().cos() * PI);
         }
-        LoadState::Loaded => {
-            state.set(AppState::Menu).unwrap();
-        }
         _ => {}
     }
 }
@@ -1,46 +1,52 @@
-mod aim;
-mod app;
-mod background;
-mod bullet;
-mod camera;
-mod character;
-mod character_lifes;
-mod collision;
-mod enemies;
-mod enemies_left;
-mod events;
-mod explosion;
-mod fps;
-mod loader;
-mod menu;
-mod rain;
-mod state;
-mod window;
-
-mod consts;
-mod utils;
-
 use bevy::prelude::*;
+use stars_rs::{
+    cli::Args,
+    replay::{Replay, ReplayPlugin},
+    rng::RngPlugin,
+    window, StarsPluginGroup,
+};
 
 fn main() {
+    let args = Args::parse(std::env::args());
+    let replay = load_replay(&args);
+
     App::new()
-        .add_plugins(DefaultPlugins.set(window::get_window_pluggin()))
-        .add_plugin(app::AppPlugin)
-        .add_plugin(events::EventsPlugin)
-        .add_plugin(camera::CameraPlugin)
-        .add_plugin(state::StatePlugin)
-        .add_plugin(loader::LoaderPlugin)
-        .add_plugin(menu::MenuPlugin)
-        .add_plugin(background::BackgroundPlugin)
-        .add_plugin(bullet::BulletPlugin)
-        .add_plugin(enemies::EnemiesPlugin)
-        .add_plugin(character::CharacterPlugin)
-        .add_plugin(explosion::ExplosionPlugin)
-        .add_plugin(collision::CollisionPlugin)
-        .add_plugin(rain::RainPlugin)
-        .add_plugin(aim::AimPlugin)
-        .add_plugin(fps::FpsTextPlugin)
-        .add_plugin(enemies_left::EnemiesLeftTextPlugin)
-        .add_plugin(character_lifes::CharacterLifesTextPlugin)
+        .add_plugins(
+            DefaultPlugins
+                .set(window::get_window_pluggin())
+                .set(AssetPlugin {
+                    // hot reload game.config.ron while tuning
+                    watch_for_changes: cfg!(all(debug_assertions, not(target_arch = "wasm32"))),
+                    ..default()
+                }),
+        )
+        .add_plugins(
+            StarsPluginGroup
+                .build()
+                .set(RngPlugin { seed: args.seed })
+                .set(ReplayPlugin { replay }),
+        )
         .run();
 }
+
+/// Reads the replay passed with `--replay`, exiting when it can't be played.
+#[cfg(not(target_arch = "wasm32"))]
+fn load_replay(args: &Args) -> Option<Replay> {
+    let replay = args.replay.as_ref().map(|path| {
+        Replay::load(path).unwrap_or_else(|error| {
+            eprintln!("Can't play {}: {}", path.display(), error);
+            std::process::exit(1);
+        })
+    });
+    if replay.as_ref().is_some_and(|replay| replay.incomplete) {
+        eprintln!("The run was played with a gamepad or touch, which replays don't keep: it may not play back the same");
+    }
+
+    replay
+}
+
+/// The web build has no files to play replays from.
+#[cfg(target_arch = "wasm32")]
+fn load_replay(_args: &Args) -> Option<Replay> {
+    None
+}
@@ -1,32 +1,44 @@
 use crate::{
     consts::POSITION_Z,
-    state::{AppState, LoaderState},
+    state::{despawn_with, AppState, LoaderState},
 };
 use bevy::prelude::*;
 
-const NORMAL_BUTTON: Color = Color::rgb(0.15, 0.15, 0.15);
-const HOVERED_BUTTON: Color = Color::rgb(0.25, 0.6, 0.25);
-const PRESSED_BUTTON: Color = Color::rgb(0.35, 0.75, 0.35);
+pub const NORMAL_BUTTON: Color = Color::rgb(0.15, 0.15, 0.15);
+pub const HOVERED_BUTTON: Color = Color::rgb(0.25, 0.6, 0.25);
+pub const PRESSED_BUTTON: Color = Color::rgb(0.35, 0.75, 0.35);
 
 #[derive(Component)]
-struct MenuButton;
+struct Menu;
 
-fn button_system(
+#[derive(Component, Clone, Copy)]
+enum MenuButton {
+    Start,
+    HighScores,
+    Controls,
+}
+
+impl MenuButton {
+    fn label(&self) -> &'static str {
+        match self {
+            MenuButton::Start => "Start",
+            MenuButton::HighScores => "High scores",
+            MenuButton::Controls => "Controls",
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn button_color(
     mut interaction_query: Query<
         (&Interaction, &mut BackgroundColor),
         (Changed<Interaction>, With<Button>),
     >,
-    mut state: ResMut<State<AppState>>,
-    loader: ResMut<LoaderState>,
-    audio: Res<Audio>,
 ) {
     for (interaction, mut color) in interaction_query.iter_mut() {
         match *interaction {
             Interaction::Clicked => {
                 *color = PRESSED_BUTTON.into();
-                state.set(AppState::Main).unwrap();
-
-                audio.play(loader.button_sound.clone());
             }
             Interaction::Hovered => {
                 *color = HOVERED_BUTTON.into();
@@ -38,51 +50,98 @@ fn button_system(
     }
 }
 
+fn button_system(
+    interaction_query: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    mut state: ResMut<State<AppState>>,
+    loader: ResMut<LoaderState>,
+    audio: Res<Audio>,
+) {
+    for (interaction, menu_button) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            match menu_button {
+                MenuButton::Start => state.set(AppState::Main).unwrap(),
+                MenuButton::HighScores => state.set(AppState::HighScores).unwrap(),
+                MenuButton::Controls => state.set(AppState::Controls).unwrap(),
+            }
+
+            audio.play(loader.button_sound.clone());
+        }
+    }
+}
+
+/// Menu styled button, children are centered.
+pub fn button_bundle(width: f32) -> ButtonBundle {
+    ButtonBundle {
+        style: Style {
+            size: Size::new(Val::Px(width), Val::Px(65.0)),
+            // center button
+            margin: UiRect::all(Val::Auto),
+            // horizontally center child text
+            justify_content: JustifyContent::Center,
+            // vertically center child text
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        background_color: NORMAL_BUTTON.into(),
+        transform: Transform::from_xyz(0.0, 0.0, POSITION_Z.menu),
+        ..default()
+    }
+}
+
+pub fn button_text(value: &str, font: Handle<Font>) -> TextBundle {
+    TextBundle {
+        text: Text::from_section(
+            value,
+            TextStyle {
+                font,
+                font_size: 40.0,
+                color: Color::rgb(0.9, 0.9, 0.9),
+            },
+        ),
+        ..default()
+    }
+}
+
 fn setup(mut commands: Commands, loader: Res<LoaderState>) {
     commands
-        .spawn(ButtonBundle {
+        .spawn(NodeBundle {
             style: Style {
-                size: Size::new(Val::Px(150.0), Val::Px(65.0)),
-                // center button
-                margin: UiRect::all(Val::Auto),
-                // horizontally center child text
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::ColumnReverse,
                 justify_content: JustifyContent::Center,
-                // vertically center child text
                 align_items: AlignItems::Center,
                 ..default()
             },
-            background_color: NORMAL_BUTTON.into(),
-            transform: Transform::from_xyz(0.0, 0.0, POSITION_Z.menu),
+            background_color: Color::NONE.into(),
             ..default()
         })
         .with_children(|parent| {
-            parent.spawn(TextBundle {
-                text: Text::from_section(
-                    "Start",
-                    TextStyle {
-                        font: loader.font.clone(),
-                        font_size: 40.0,
-                        color: Color::rgb(0.9, 0.9, 0.9),
-                    },
-                ),
-                ..default()
-            });
-        })
-        .insert(MenuButton);
-}
+            for menu_button in [
+                MenuButton::Start,
+                MenuButton::HighScores,
+                MenuButton::Controls,
+            ] {
+                let mut button = button_bundle(300.0);
+                button.style.margin = UiRect::all(Val::Px(10.0));
 
-fn remove_button(mut commands: Commands, query: Query<Entity, With<MenuButton>>) {
-    for entity in query.iter() {
-        commands.entity(entity).despawn_recursive();
-    }
+                parent
+                    .spawn(button)
+                    .with_children(|parent| {
+                        parent.spawn(button_text(menu_button.label(), loader.font.clone()));
+                    })
+                    .insert(menu_button);
+            }
+        })
+        .insert(Menu);
 }
 
 pub struct MenuPlugin;
 
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system_set(SystemSet::on_enter(AppState::Menu).with_system(setup))
+        app.add_system(button_color)
+            .add_system_set(SystemSet::on_enter(AppState::Menu).with_system(setup))
             .add_system_set(SystemSet::on_update(AppState::Menu).with_system(button_system))
-            .add_system_set(SystemSet::on_exit(AppState::Menu).with_system(remove_button));
+            .add_system_set(SystemSet::on_exit(AppState::Menu).with_system(despawn_with::<Menu>));
     }
 }
@@ -0,0 +1,124 @@
+use crate::{
+    controls::{Action, ActionInputMut},
+    menu::{button_bundle, button_text},
+    state::{despawn_with, AppState, LoaderState},
+};
+use bevy::{prelude::*, window::WindowFocused};
+
+#[derive(Component)]
+struct PauseMenu;
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PauseButton {
+    Resume,
+    Restart,
+    Menu,
+}
+
+impl PauseButton {
+    fn label(&self) -> &'static str {
+        match self {
+            PauseButton::Resume => "Resume",
+            PauseButton::Restart => "Restart",
+            PauseButton::Menu => "Quit to menu",
+        }
+    }
+}
+
+fn pause(
+    mut actions: ActionInputMut,
+    mut window_focused_events: EventReader<WindowFocused>,
+    mut state: ResMut<State<AppState>>,
+) {
+    let focus_lost = window_focused_events.iter().any(|event| !event.focused);
+
+    if actions.just_pressed(Action::Pause) || focus_lost {
+        actions.reset(Action::Pause);
+        // Another transition may already be queued this frame, that one wins.
+        let _ = state.push(AppState::Paused);
+    }
+}
+
+fn resume(mut actions: ActionInputMut, mut state: ResMut<State<AppState>>) {
+    if actions.just_pressed(Action::Pause) {
+        actions.reset(Action::Pause);
+        let _ = state.pop();
+    }
+}
+
+fn setup(mut commands: Commands, loader: Res<LoaderState>) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Paused",
+                TextStyle {
+                    font: loader.font.clone(),
+                    font_size: 40.0,
+                    color: Color::WHITE,
+                },
+            ));
+
+            for pause_button in [PauseButton::Resume, PauseButton::Restart, PauseButton::Menu] {
+                let mut button = button_bundle(320.0);
+                button.style.margin = UiRect::all(Val::Px(10.0));
+
+                parent
+                    .spawn(button)
+                    .with_children(|parent| {
+                        parent.spawn(button_text(pause_button.label(), loader.font.clone()));
+                    })
+                    .insert(pause_button);
+            }
+        })
+        .insert(PauseMenu);
+}
+
+fn button_system(
+    interaction_query: Query<(&Interaction, &PauseButton), Changed<Interaction>>,
+    mut state: ResMut<State<AppState>>,
+    loader: Res<LoaderState>,
+    audio: Res<Audio>,
+) {
+    for (interaction, pause_button) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            // Resuming with the keyboard in the same frame already queued a transition.
+            let result = match pause_button {
+                PauseButton::Resume => state.pop(),
+                PauseButton::Restart => state.replace(AppState::Main),
+                PauseButton::Menu => state.replace(AppState::Menu),
+            };
+
+            if result.is_ok() {
+                audio.play(loader.button_sound.clone());
+            }
+        }
+    }
+}
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_update(AppState::Main).with_system(pause))
+            .add_system_set(SystemSet::on_enter(AppState::Paused).with_system(setup))
+            .add_system_set(
+                SystemSet::on_update(AppState::Paused)
+                    .with_system(resume.before(button_system))
+                    .with_system(button_system),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Paused).with_system(despawn_with::<PauseMenu>),
+            );
+    }
+}
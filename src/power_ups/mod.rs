@@ -0,0 +1,317 @@
+use crate::utils;
+use crate::{
+    character::{set_inactive, Character, CharacterActive, CharacterInactiveTimer, CharacterLifes},
+    config::{GameConfig, PowerUpsConfig},
+    consts::{POSITION_Z, WINDOW_SIZE},
+    events::{BombEvent, CharacterLifesEvent, CollisionEvent, CollisionKind, WeaponEvent},
+    rng::GameRng,
+    state::{despawn_with, AppState, LoaderState},
+    timestep::{
+        interpolate, on_fixed_update, FixedUpdateLabel, FixedUpdateStage, PreviousPosition,
+        TIMESTEP,
+    },
+    utils::{random_in_range, BoundingRect, GetBoundingRect, GetPosition},
+    weapon::Weapon,
+};
+use bevy::{prelude::*, utils::Duration};
+use rand::Rng;
+use std::f32::consts::{FRAC_PI_4, TAU};
+
+static POWER_UP_SIZE: utils::Size = utils::Size {
+    width: 24.0,
+    height: 24.0,
+};
+/// Visibility toggles per second while a pickup is about to expire.
+static BLINK_RATE: f32 = 8.0;
+
+/// Effect of a pickup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowerUpKind {
+    ExtraLife,
+    Shield,
+    RapidFire,
+    WeaponUpgrade,
+    Bomb,
+}
+
+/// Relative odds of each kind in the drop table.
+static DROP_WEIGHTS: [(PowerUpKind, u32); 5] = [
+    (PowerUpKind::ExtraLife, 1),
+    (PowerUpKind::Shield, 3),
+    (PowerUpKind::RapidFire, 3),
+    (PowerUpKind::WeaponUpgrade, 2),
+    (PowerUpKind::Bomb, 1),
+];
+
+impl PowerUpKind {
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let total: u32 = DROP_WEIGHTS.iter().map(|(_, weight)| weight).sum();
+        let mut roll = rng.gen_range(0..total);
+
+        for (kind, weight) in DROP_WEIGHTS.iter() {
+            if roll < *weight {
+                return *kind;
+            }
+            roll -= weight;
+        }
+
+        unreachable!()
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            PowerUpKind::ExtraLife => Color::LIME_GREEN,
+            PowerUpKind::Shield => Color::CYAN,
+            PowerUpKind::RapidFire => Color::YELLOW,
+            PowerUpKind::WeaponUpgrade => Color::FUCHSIA,
+            PowerUpKind::Bomb => Color::ORANGE_RED,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PowerUpKind::ExtraLife => "+",
+            PowerUpKind::Shield => "S",
+            PowerUpKind::RapidFire => "R",
+            PowerUpKind::WeaponUpgrade => "W",
+            PowerUpKind::Bomb => "B",
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct PowerUp {
+    pub kind: PowerUpKind,
+    pub position: Vec2,
+    speed: Vec2,
+    lifetime: Timer,
+}
+
+impl PowerUp {
+    pub fn new(kind: PowerUpKind, position: Vec2, speed: Vec2, lifetime: f32) -> Self {
+        Self {
+            kind,
+            position,
+            speed,
+            lifetime: Timer::from_seconds(lifetime, TimerMode::Once),
+        }
+    }
+
+    fn remaining_secs(&self) -> f32 {
+        (self.lifetime.duration() - self.lifetime.elapsed()).as_secs_f32()
+    }
+}
+
+impl GetBoundingRect for PowerUp {
+    fn get_bounding_rect(&self) -> BoundingRect {
+        BoundingRect {
+            x: self.position.x,
+            y: self.position.y,
+            width: POWER_UP_SIZE.width,
+            height: POWER_UP_SIZE.height,
+        }
+    }
+}
+
+impl GetPosition for PowerUp {
+    fn get_position(&self) -> Vec2 {
+        self.position
+    }
+}
+
+fn add_power_up(
+    commands: &mut Commands,
+    loader: &LoaderState,
+    config: &PowerUpsConfig,
+    kind: PowerUpKind,
+    position: Vec2,
+    speed: Vec2,
+) {
+    commands
+        .spawn(SpatialBundle::from_transform(Transform::from_xyz(
+            position.x,
+            position.y,
+            POSITION_Z.bullet,
+        )))
+        .insert(PreviousPosition(position))
+        .insert(PowerUp::new(kind, position, speed, config.lifetime))
+        .with_children(|parent| {
+            parent.spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: kind.color(),
+                    custom_size: Some(Vec2::new(POWER_UP_SIZE.width, POWER_UP_SIZE.height)),
+                    ..default()
+                },
+                transform: Transform::from_rotation(Quat::from_rotation_z(FRAC_PI_4)),
+                ..default()
+            });
+            parent.spawn(Text2dBundle {
+                text: Text::from_section(
+                    kind.label(),
+                    TextStyle {
+                        font: loader.font.clone(),
+                        font_size: 16.0,
+                        color: Color::BLACK,
+                    },
+                )
+                .with_alignment(TextAlignment::CENTER),
+                transform: Transform::from_xyz(0.0, 0.0, 0.1),
+                ..default()
+            });
+        });
+}
+
+/// Rolls the drop table for an enemy destroyed at `position`.
+pub fn drop_power_up(
+    commands: &mut Commands,
+    rng: &mut GameRng,
+    loader: &LoaderState,
+    config: &PowerUpsConfig,
+    position: Vec2,
+) {
+    if rng.gen::<f32>() >= config.drop_chance {
+        return;
+    }
+
+    let kind = PowerUpKind::random(rng);
+    let speed = Vec2::from_angle(random_in_range(rng, 0.0, TAU)) * config.drift_speed;
+
+    add_power_up(commands, loader, config, kind, position, speed);
+}
+
+fn update_power_ups(
+    mut query: Query<(Entity, &mut PowerUp, &mut PreviousPosition)>,
+    mut commands: Commands,
+) {
+    let delta_seconds = TIMESTEP as f32;
+    let max_x = (WINDOW_SIZE.width - POWER_UP_SIZE.width) / 2.0;
+    let max_y = (WINDOW_SIZE.height - POWER_UP_SIZE.height) / 2.0;
+
+    for (entity, mut power_up, mut previous_position) in query.iter_mut() {
+        if power_up
+            .lifetime
+            .tick(Duration::from_secs_f64(TIMESTEP))
+            .finished()
+        {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        previous_position.0 = power_up.position;
+
+        let speed = power_up.speed;
+        power_up.position += speed * delta_seconds;
+
+        if power_up.position.x.abs() > max_x {
+            power_up.speed.x = -power_up.position.x.signum() * speed.x.abs();
+        }
+        if power_up.position.y.abs() > max_y {
+            power_up.speed.y = -power_up.position.y.signum() * speed.y.abs();
+        }
+    }
+}
+
+fn blink_power_ups(config: Res<GameConfig>, mut query: Query<(&PowerUp, &mut Visibility)>) {
+    for (power_up, mut visibility) in query.iter_mut() {
+        let remaining = power_up.remaining_secs();
+
+        visibility.is_visible = remaining > config.power_ups.blink_time
+            || ((remaining * BLINK_RATE) as u32).is_multiple_of(2);
+    }
+}
+
+/// Applies the effects of the power-ups the character picked up.
+#[allow(clippy::too_many_arguments)]
+fn collect_power_ups(
+    mut collision_events: EventReader<CollisionEvent>,
+    config: Res<GameConfig>,
+    loader: Res<LoaderState>,
+    audio: Res<Audio>,
+    mut character_query: Query<(
+        &Character,
+        &mut CharacterActive,
+        &mut CharacterInactiveTimer,
+        &mut Weapon,
+    )>,
+    mut character_lifes_query: Query<&mut CharacterLifes>,
+    mut character_lifes_events: EventWriter<CharacterLifesEvent>,
+    mut weapon_events: EventWriter<WeaponEvent>,
+    mut bomb_events: EventWriter<BombEvent>,
+    query: Query<(Entity, &PowerUp)>,
+    mut commands: Commands,
+) {
+    for collision_event in collision_events.iter() {
+        if collision_event.kind != CollisionKind::Pickup {
+            continue;
+        }
+
+        let (
+            Ok((entity, power_up)),
+            Ok((character, mut character_active, mut inactive_timer, mut weapon)),
+        ) = (
+            query.get(collision_event.a),
+            character_query.get_mut(collision_event.b),
+        )
+        else {
+            continue;
+        };
+
+        commands.entity(entity).despawn_recursive();
+
+        match power_up.kind {
+            PowerUpKind::ExtraLife => {
+                let mut character_lifes = character_lifes_query.single_mut();
+                character_lifes.increase();
+
+                character_lifes_events.send(CharacterLifesEvent {
+                    character_lifes: character_lifes.lifes,
+                });
+            }
+            PowerUpKind::Shield => set_inactive(
+                &mut character_active,
+                &mut inactive_timer,
+                config.power_ups.shield_duration,
+            ),
+            PowerUpKind::RapidFire => weapon.boost(config.power_ups.boost_duration),
+            PowerUpKind::WeaponUpgrade => {
+                let kind = weapon.kind.upgrade();
+                if kind != weapon.kind {
+                    weapon.kind = kind;
+                    weapon_events.send(WeaponEvent { weapon: kind });
+                }
+            }
+            PowerUpKind::Bomb => bomb_events.send(BombEvent {
+                position: character.position,
+                radius: f32::INFINITY,
+            }),
+        }
+
+        audio.play(loader.button_sound.clone());
+    }
+}
+
+pub struct PowerUpsPlugin;
+
+impl Plugin for PowerUpsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(AppState::Main)
+                .with_system(interpolate::<PowerUp>)
+                .with_system(blink_power_ups),
+        )
+        .add_system_set_to_stage(
+            FixedUpdateStage,
+            on_fixed_update(AppState::Main)
+                .label(FixedUpdateLabel::Movement)
+                .with_system(update_power_ups),
+        )
+        .add_system_set_to_stage(
+            FixedUpdateStage,
+            on_fixed_update(AppState::Main)
+                .label(FixedUpdateLabel::Damage)
+                .after(FixedUpdateLabel::Collision)
+                .with_system(collect_power_ups),
+        )
+        .add_system_set(SystemSet::on_exit(AppState::Main).with_system(despawn_with::<PowerUp>));
+    }
+}
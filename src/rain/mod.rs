@@ -1,10 +1,13 @@
 use crate::utils;
 use crate::{
+    config::GameConfig,
     consts::{POSITION_Z, WINDOW_SIZE},
-    state::AppState,
+    rng::GameRng,
+    state::{despawn_with, AppState},
     utils::random_in_range,
 };
 use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng};
 
 static SPEED_X: f32 = 10.0;
 static MIN_SPEED_Y: f32 = 130.0;
@@ -13,35 +16,41 @@ static DROP_SIZE: utils::Size = utils::Size {
     width: 2.0,
     height: 8.0,
 };
-static DROP_COUNT: u32 = 200;
+static RAIN_STREAM: u64 = 1;
+
+#[derive(Resource, Deref, DerefMut)]
+struct RainRng(StdRng);
 
 #[derive(Component)]
 struct Drop {
     speed: Vec2,
 }
 
-impl Default for Drop {
-    fn default() -> Self {
+impl Drop {
+    fn new<R: Rng + ?Sized>(rng: &mut R) -> Self {
         Self {
-            speed: Vec2::new(SPEED_X, random_in_range(MIN_SPEED_Y, MAX_SPEED_Y)),
+            speed: Vec2::new(SPEED_X, random_in_range(rng, MIN_SPEED_Y, MAX_SPEED_Y)),
         }
     }
 }
 
-fn get_initial_position() -> Vec2 {
+fn get_initial_position<R: Rng + ?Sized>(rng: &mut R) -> Vec2 {
     let x = random_in_range(
+        rng,
         -WINDOW_SIZE.width / 2.0 - DROP_SIZE.width,
         WINDOW_SIZE.width / 2.0,
     );
     let y = random_in_range(
+        rng,
         -WINDOW_SIZE.height / 2.0,
         (WINDOW_SIZE.height + DROP_SIZE.height) / 2.0,
     );
     Vec2::new(x, y)
 }
 
-fn get_restart_position() -> Vec2 {
+fn get_restart_position<R: Rng + ?Sized>(rng: &mut R) -> Vec2 {
     let x = random_in_range(
+        rng,
         -WINDOW_SIZE.width / 2.0 - DROP_SIZE.width,
         WINDOW_SIZE.width / 2.0,
     );
@@ -49,9 +58,11 @@ fn get_restart_position() -> Vec2 {
     Vec2::new(x, y)
 }
 
-fn add_rain(mut commands: Commands) {
-    for _ in 0..DROP_COUNT {
-        let position = get_initial_position();
+fn add_rain(mut commands: Commands, game_rng: Res<GameRng>, config: Res<GameConfig>) {
+    let mut rng = game_rng.stream(RAIN_STREAM);
+
+    for _ in 0..config.rain.drop_count {
+        let position = get_initial_position(&mut rng);
         commands
             .spawn(SpriteBundle {
                 sprite: Sprite {
@@ -63,18 +74,24 @@ fn add_rain(mut commands: Commands) {
                     .with_rotation(Quat::from_rotation_z(0.15)),
                 ..default()
             })
-            .insert(Drop::default());
+            .insert(Drop::new(&mut rng));
     }
+
+    commands.insert_resource(RainRng(rng));
 }
 
-fn update_rain(time: Res<Time>, mut query: Query<(&Drop, &mut Transform)>) {
+fn update_rain(
+    time: Res<Time>,
+    mut rng: ResMut<RainRng>,
+    mut query: Query<(&Drop, &mut Transform)>,
+) {
     let delta_seconds = time.delta_seconds();
 
     for (drop, mut transform) in query.iter_mut() {
         if transform.translation.x > (WINDOW_SIZE.width + DROP_SIZE.width) / 2.0
             || transform.translation.y < -(WINDOW_SIZE.height + DROP_SIZE.height) / 2.0
         {
-            let position = get_restart_position();
+            let position = get_restart_position(&mut **rng);
             transform.translation.x = position.x;
             transform.translation.y = position.y;
         } else {
@@ -89,6 +106,7 @@ pub struct RainPlugin;
 impl Plugin for RainPlugin {
     fn build(&self, app: &mut App) {
         app.add_system_set(SystemSet::on_enter(AppState::Main).with_system(add_rain))
-            .add_system_set(SystemSet::on_update(AppState::Main).with_system(update_rain));
+            .add_system_set(SystemSet::on_update(AppState::Main).with_system(update_rain))
+            .add_system_set(SystemSet::on_exit(AppState::Main).with_system(despawn_with::<Drop>));
     }
 }
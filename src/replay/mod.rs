@@ -0,0 +1,476 @@
+use crate::{
+    config::GameConfig,
+    controls::{Binding, Keymap},
+    rng::GameRng,
+    state::AppState,
+    storage::Storage,
+};
+use bevy::{
+    input::{
+        gamepad::{GamepadEventRaw, GamepadEventType},
+        keyboard::KeyboardInput,
+        mouse::{MouseButtonInput, MouseScrollUnit, MouseWheel},
+        touch::TouchInput,
+        InputSystem,
+    },
+    prelude::*,
+    time::{TimeSystem, TimeUpdateStrategy},
+    ui::UiSystem,
+    utils::Duration,
+    window::WindowId,
+};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Version of the replay format, replays of other versions are refused.
+pub static REPLAY_VERSION: u32 = 2;
+pub static STORAGE_NAME: &str = "replay";
+
+/// Input of one frame. Only presses and releases since the previous frame are kept,
+/// the cursor and the wheel only when they moved.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ReplayFrame {
+    /// Length of the frame in nanoseconds.
+    pub delta: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pressed: Vec<Binding>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub released: Vec<Binding>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<Vec2>,
+    /// Wheel notches, positive away from the player.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub wheel: i32,
+}
+
+fn is_zero(value: &i32) -> bool {
+    *value == 0
+}
+
+/// One run: the seed it started with, the keymap the input went through and the
+/// keyboard and mouse input of every frame, from the frame it started in.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    pub keymap: Keymap,
+    /// [`config_hash`] of the config the run was played with.
+    pub config: u64,
+    pub frames: Vec<ReplayFrame>,
+    /// Gamepad or touch input was used during the run. It isn't recorded, so the
+    /// replay may not play back the same.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub incomplete: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+impl Default for Replay {
+    fn default() -> Self {
+        Self::new(0, Keymap::default(), &GameConfig::default())
+    }
+}
+
+/// Only the version of a replay, read before the rest as other versions may not parse.
+#[derive(Deserialize)]
+struct ReplayHeader {
+    version: u32,
+}
+
+/// FNV-1a hash of `config`, a replay played with another config won't play the same.
+pub fn config_hash(config: &GameConfig) -> u64 {
+    format!("{:?}", config)
+        .bytes()
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+}
+
+/// Why a replay can't be played.
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    /// The replay is of another `REPLAY_VERSION`.
+    Version(u32),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Io(error) => write!(f, "{}", error),
+            ReplayError::Parse(error) => write!(f, "not a replay: {}", error),
+            ReplayError::Version(version) => write!(
+                f,
+                "replay version {} can't be played, expected {}",
+                version, REPLAY_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReplayError::Io(error) => Some(error),
+            ReplayError::Parse(error) => Some(error),
+            ReplayError::Version(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ReplayError {
+    fn from(error: std::io::Error) -> Self {
+        ReplayError::Io(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for ReplayError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        ReplayError::Parse(error)
+    }
+}
+
+impl Replay {
+    pub fn new(seed: u64, keymap: Keymap, config: &GameConfig) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            seed,
+            keymap,
+            config: config_hash(config),
+            frames: Vec::new(),
+            incomplete: false,
+        }
+    }
+
+    pub fn from_ron(text: &str) -> Result<Self, ReplayError> {
+        let header: ReplayHeader = ron::from_str(text)?;
+        if header.version != REPLAY_VERSION {
+            return Err(ReplayError::Version(header.version));
+        }
+
+        Ok(ron::from_str(text)?)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: &std::path::Path) -> Result<Self, ReplayError> {
+        Self::from_ron(&std::fs::read_to_string(path)?)
+    }
+}
+
+/// Records every run played with live input, the replay is saved when the run ends.
+#[derive(Resource, Default)]
+pub struct Recorder {
+    replay: Option<Replay>,
+    /// Keys and buttons held in the last recorded frame.
+    recorded: Vec<Binding>,
+    /// Input of the current frame, recorded at its end if a run is going on.
+    pressed: Vec<Binding>,
+    cursor: Option<Vec2>,
+    wheel: i32,
+    /// Gamepad or touch input came in this frame.
+    unrecorded: bool,
+}
+
+impl Recorder {
+    /// Replay of the run going on, if it's recorded.
+    pub fn replay(&self) -> Option<&Replay> {
+        self.replay.as_ref()
+    }
+
+    fn start(&mut self, seed: u64, keymap: &Keymap, config: &GameConfig) {
+        self.replay = Some(Replay::new(seed, keymap.clone(), config));
+        self.recorded.clear();
+    }
+
+    fn record(&mut self, delta: Duration) {
+        let Some(replay) = self.replay.as_mut() else {
+            return;
+        };
+
+        replay.frames.push(ReplayFrame {
+            delta: delta.as_nanos() as u64,
+            pressed: difference(&self.pressed, &self.recorded),
+            released: difference(&self.recorded, &self.pressed),
+            cursor: self.cursor,
+            wheel: self.wheel,
+        });
+        replay.incomplete |= self.unrecorded;
+        self.recorded = self.pressed.clone();
+    }
+
+    fn finish(&mut self, storage: &Storage) {
+        if let Some(replay) = self.replay.take() {
+            info!("replay of {} frames saved", replay.frames.len());
+            storage.save(STORAGE_NAME, &replay);
+        }
+    }
+}
+
+fn difference(bindings: &[Binding], other: &[Binding]) -> Vec<Binding> {
+    bindings
+        .iter()
+        .filter(|binding| !other.contains(binding))
+        .copied()
+        .collect()
+}
+
+/// Replay fed to the game in place of live input, `frame` is the next one to play.
+/// The player's own keymap is put aside while the replay's is in use.
+#[derive(Resource)]
+struct Playback {
+    replay: Replay,
+    frame: usize,
+    started: bool,
+    keymap: Option<Keymap>,
+}
+
+impl Playback {
+    fn current_frame(&self) -> Option<&ReplayFrame> {
+        self.replay.frames.get(self.frame)
+    }
+}
+
+/// Seeds the run, from the replay when one is played, and starts recording otherwise.
+/// A replay is played with its own keymap.
+fn start_run(
+    mut rng: ResMut<GameRng>,
+    mut recorder: ResMut<Recorder>,
+    playback: Option<ResMut<Playback>>,
+    mut keymap: ResMut<Keymap>,
+    config: Res<GameConfig>,
+    storage: Res<Storage>,
+) {
+    if let Some(mut playback) = playback {
+        if !playback.started {
+            playback.started = true;
+            *rng = GameRng::new(playback.replay.seed);
+            playback.keymap = Some(std::mem::replace(
+                &mut *keymap,
+                playback.replay.keymap.clone(),
+            ));
+            if playback.replay.config != config_hash(&config) {
+                warn!("the replay was recorded with another game config, it may not play back the same");
+            }
+            return;
+        }
+    }
+
+    recorder.finish(&storage);
+    let seed = rng.next_u64();
+    *rng = GameRng::new(seed);
+    recorder.start(seed, &keymap, &config);
+}
+
+/// Takes note of the input as the game sees it, before any system has reset a press.
+fn read_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    mut cursor_moved_events: EventReader<CursorMoved>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    mut gamepad_events: EventReader<GamepadEventRaw>,
+    mut touch_events: EventReader<TouchInput>,
+    mut recorder: ResMut<Recorder>,
+) {
+    recorder.pressed = keyboard_input
+        .get_pressed()
+        .map(|key_code| Binding::Key(*key_code))
+        .chain(
+            mouse_button_input
+                .get_pressed()
+                .map(|mouse_button| Binding::Mouse(*mouse_button)),
+        )
+        .collect();
+    recorder.cursor = cursor_moved_events
+        .iter()
+        .last()
+        .map(|event| event.position);
+    recorder.wheel = mouse_wheel_events
+        .iter()
+        .map(|event| event.y.signum() as i32)
+        .sum();
+    let gamepad_used = gamepad_events.iter().any(|event| {
+        matches!(
+            event.event_type,
+            GamepadEventType::ButtonChanged(..) | GamepadEventType::AxisChanged(..)
+        )
+    });
+    let touched = touch_events.iter().count() > 0;
+    recorder.unrecorded = gamepad_used || touched;
+}
+
+fn record_frame(
+    time: Res<Time>,
+    state: Res<State<AppState>>,
+    storage: Res<Storage>,
+    mut recorder: ResMut<Recorder>,
+) {
+    recorder.record(time.delta());
+
+    if !matches!(state.current(), AppState::Main | AppState::Paused) {
+        recorder.finish(&storage);
+    }
+}
+
+/// Gives every frame of the replay the length it had when recorded.
+fn play_time(
+    playback: Option<Res<Playback>>,
+    time: Res<Time>,
+    mut time_update_strategy: ResMut<TimeUpdateStrategy>,
+) {
+    let Some(frame) = playback
+        .as_ref()
+        .and_then(|playback| playback.current_frame())
+    else {
+        return;
+    };
+
+    let last_update = time.last_update().unwrap_or_else(|| time.startup());
+    *time_update_strategy =
+        TimeUpdateStrategy::ManualInstant(last_update + Duration::from_nanos(frame.delta));
+}
+
+/// Drops the input of the devices while a replay is played.
+fn block_live_input(
+    playback: Option<Res<Playback>>,
+    mut keyboard_events: ResMut<Events<KeyboardInput>>,
+    mut mouse_button_events: ResMut<Events<MouseButtonInput>>,
+    mut cursor_moved_events: ResMut<Events<CursorMoved>>,
+    mut mouse_wheel_events: ResMut<Events<MouseWheel>>,
+    mut gamepad_events: ResMut<Events<GamepadEventRaw>>,
+    mut touch_events: ResMut<Events<TouchInput>>,
+) {
+    if playback.is_none() {
+        return;
+    }
+
+    keyboard_events.clear();
+    mouse_button_events.clear();
+    cursor_moved_events.clear();
+    mouse_wheel_events.clear();
+    gamepad_events.clear();
+    touch_events.clear();
+}
+
+/// Applies the input of the current frame. Until the run starts the first frame is
+/// held, as the run starts in a frame of its own.
+fn play_input(
+    playback: Option<Res<Playback>>,
+    mut keyboard_input: ResMut<Input<KeyCode>>,
+    mut mouse_button_input: ResMut<Input<MouseButton>>,
+    mut cursor_moved_events: EventWriter<CursorMoved>,
+    mut mouse_wheel_events: EventWriter<MouseWheel>,
+) {
+    let Some(playback) = playback else {
+        return;
+    };
+    let Some(frame) = playback.current_frame() else {
+        return;
+    };
+
+    if !playback.started {
+        keyboard_input.reset_all();
+        mouse_button_input.reset_all();
+    }
+
+    for binding in frame.pressed.iter() {
+        match binding {
+            Binding::Key(key_code) => keyboard_input.press(*key_code),
+            Binding::Mouse(mouse_button) => mouse_button_input.press(*mouse_button),
+            Binding::Gamepad(_) => {}
+        }
+    }
+
+    for binding in frame.released.iter() {
+        match binding {
+            Binding::Key(key_code) => keyboard_input.release(*key_code),
+            Binding::Mouse(mouse_button) => mouse_button_input.release(*mouse_button),
+            Binding::Gamepad(_) => {}
+        }
+    }
+
+    if let Some(position) = frame.cursor {
+        cursor_moved_events.send(CursorMoved {
+            id: WindowId::primary(),
+            position,
+        });
+    }
+
+    if playback.started {
+        let notches = (0..frame.wheel.unsigned_abs()).map(|_| MouseWheel {
+            unit: MouseScrollUnit::Line,
+            x: 0.0,
+            y: frame.wheel.signum() as f32,
+        });
+        mouse_wheel_events.send_batch(notches);
+    }
+}
+
+/// Starts the run of the replay as soon as the menu is up.
+fn start_playback(playback: Option<Res<Playback>>, mut state: ResMut<State<AppState>>) {
+    if playback.is_some_and(|playback| !playback.started) {
+        state.set(AppState::Main).unwrap();
+    }
+}
+
+/// Moves on to the next frame once the run has started, and hands control back to
+/// the player, with their keymap, after the last one.
+fn advance_playback(
+    playback: Option<ResMut<Playback>>,
+    mut time_update_strategy: ResMut<TimeUpdateStrategy>,
+    mut keymap: ResMut<Keymap>,
+    mut commands: Commands,
+) {
+    let Some(mut playback) = playback else {
+        return;
+    };
+    if !playback.started {
+        return;
+    }
+
+    playback.frame += 1;
+    if playback.current_frame().is_none() {
+        info!("replay finished");
+        *time_update_strategy = TimeUpdateStrategy::Automatic;
+        if let Some(player_keymap) = playback.keymap.take() {
+            *keymap = player_keymap;
+        }
+        commands.remove_resource::<Playback>();
+    }
+}
+
+/// Records runs, or plays `replay` back: the first run then starts by itself and
+/// replays the recorded input.
+#[derive(Default)]
+pub struct ReplayPlugin {
+    pub replay: Option<Replay>,
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Recorder>()
+            .add_system_to_stage(CoreStage::First, play_time.at_start().before(TimeSystem))
+            .add_system_to_stage(CoreStage::PreUpdate, block_live_input.before(InputSystem))
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                play_input.after(InputSystem).after(UiSystem::Focus),
+            )
+            .add_system_to_stage(CoreStage::PreUpdate, read_input.after(play_input))
+            .add_system_to_stage(CoreStage::PostUpdate, advance_playback)
+            .add_system_to_stage(CoreStage::Last, record_frame)
+            .add_system_set(SystemSet::on_enter(AppState::Main).with_system(start_run))
+            .add_system_set(SystemSet::on_update(AppState::Menu).with_system(start_playback));
+
+        if let Some(replay) = self.replay.clone() {
+            app.insert_resource(Playback {
+                replay,
+                frame: 0,
+                started: false,
+                keymap: None,
+            });
+        }
+    }
+}
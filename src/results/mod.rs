@@ -0,0 +1,194 @@
+use crate::{
+    config::GameConfig,
+    events::{CharacterLifesEvent, EnemiesLeftEvent},
+    high_scores::{HighScores, NameEntry},
+    menu::{button_bundle, button_text},
+    score::Score,
+    state::{despawn_with, AppState, LoaderState},
+    stats::Stats,
+    waves::Wave,
+};
+use bevy::prelude::*;
+
+static RESULTS_DELAY: f32 = 1.5;
+
+#[derive(Resource)]
+struct Outcome {
+    state: AppState,
+    timer: Timer,
+}
+
+impl Outcome {
+    fn new(state: AppState) -> Self {
+        Self {
+            state,
+            timer: Timer::from_seconds(RESULTS_DELAY, TimerMode::Once),
+        }
+    }
+}
+
+#[derive(Component)]
+struct Results;
+
+#[derive(Component)]
+pub struct PlayAgainButton;
+
+fn check_outcome(
+    mut character_lifes_events: EventReader<CharacterLifesEvent>,
+    mut enemies_left_events: EventReader<EnemiesLeftEvent>,
+    outcome: Option<Res<Outcome>>,
+    config: Res<GameConfig>,
+    wave_query: Query<&Wave>,
+    mut commands: Commands,
+) {
+    let mut state = outcome.map(|outcome| outcome.state.clone());
+
+    for character_lifes_event in character_lifes_events.iter() {
+        if character_lifes_event.character_lifes == 0 && state.is_none() {
+            state = Some(AppState::GameOver);
+            commands.insert_resource(Outcome::new(AppState::GameOver));
+        }
+    }
+
+    for enemies_left_event in enemies_left_events.iter() {
+        let final_wave = wave_query.get_single().is_ok_and(|wave| {
+            wave.number >= config.waves.final_wave && !wave.boss_ahead(&config.boss)
+        });
+
+        if enemies_left_event.enemies_left == 0 && final_wave && state.is_none() {
+            state = Some(AppState::Victory);
+            commands.insert_resource(Outcome::new(AppState::Victory));
+        }
+    }
+}
+
+fn update_outcome(
+    time: Res<Time>,
+    outcome: Option<ResMut<Outcome>>,
+    mut state: ResMut<State<AppState>>,
+) {
+    if let Some(mut outcome) = outcome {
+        if outcome.timer.tick(time.delta()).just_finished() {
+            state.set(outcome.state.clone()).unwrap();
+        }
+    }
+}
+
+fn remove_outcome(mut commands: Commands) {
+    commands.remove_resource::<Outcome>();
+}
+
+fn setup(
+    mut commands: Commands,
+    loader: Res<LoaderState>,
+    state: Res<State<AppState>>,
+    stats: Res<Stats>,
+    score: Res<Score>,
+    high_scores: Res<HighScores>,
+) {
+    let (title, title_color) = match state.current() {
+        AppState::Victory => ("Victory", Color::GOLD),
+        _ => ("Game Over", Color::TOMATO),
+    };
+
+    let style = TextStyle {
+        font: loader.font.clone(),
+        font_size: 24.0,
+        color: Color::WHITE,
+    };
+
+    let lines = [
+        format!("Score: {}", score.points),
+        format!("Wave: {}", stats.wave),
+        format!("Time: {:.1}s", stats.time),
+        format!("Hits: {}", stats.hits),
+        format!("Accuracy: {:.0}%", stats.accuracy() * 100.0),
+    ];
+
+    let mut button = button_bundle(250.0);
+    button.style.margin = UiRect::all(Val::Px(20.0));
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::NONE.into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                title,
+                TextStyle {
+                    font_size: 40.0,
+                    color: title_color,
+                    ..style.clone()
+                },
+            ));
+
+            for line in lines {
+                parent.spawn(TextBundle::from_section(line, style.clone()));
+            }
+
+            if high_scores.qualifies(score.points) {
+                parent
+                    .spawn(TextBundle::from_sections([
+                        TextSection::new("New record! Name: ", style.clone()),
+                        TextSection::new(
+                            "_",
+                            TextStyle {
+                                color: Color::GOLD,
+                                ..style.clone()
+                            },
+                        ),
+                    ]))
+                    .insert(NameEntry::default());
+            }
+
+            parent
+                .spawn(button)
+                .with_children(|parent| {
+                    parent.spawn(button_text("Play again", loader.font.clone()));
+                })
+                .insert(PlayAgainButton);
+        })
+        .insert(Results);
+}
+
+fn play_again(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<PlayAgainButton>)>,
+    mut state: ResMut<State<AppState>>,
+    loader: Res<LoaderState>,
+    audio: Res<Audio>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            state.set(AppState::Main).unwrap();
+
+            audio.play(loader.button_sound.clone());
+        }
+    }
+}
+
+pub struct ResultsPlugin;
+
+impl Plugin for ResultsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(AppState::Main)
+                .with_system(check_outcome.before(update_outcome))
+                .with_system(update_outcome),
+        )
+        .add_system_set(SystemSet::on_exit(AppState::Main).with_system(remove_outcome));
+
+        for state in [AppState::GameOver, AppState::Victory] {
+            app.add_system_set(SystemSet::on_enter(state.clone()).with_system(setup))
+                .add_system_set(SystemSet::on_update(state.clone()).with_system(play_again))
+                .add_system_set(SystemSet::on_exit(state).with_system(despawn_with::<Results>));
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Error, RngCore, SeedableRng};
+
+/// Source of randomness for everything that affects gameplay.
+///
+/// The same seed always produces the same sequence, so a session can be reproduced from
+/// the seed printed at startup. Every run is reseeded from it when it starts, and the
+/// run seed goes into its replay.
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Independent generator derived from the seed, for consumers that must not shift
+    /// the gameplay sequence (e.g. cosmetic effects).
+    pub fn stream(&self, id: u64) -> StdRng {
+        StdRng::seed_from_u64(self.seed ^ id.rotate_left(32))
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+/// Inserts [`GameRng`]. Without a seed a random one is picked and logged.
+#[derive(Default)]
+pub struct RngPlugin {
+    pub seed: Option<u64>,
+}
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        let seed = self.seed.unwrap_or_else(rand::random);
+        info!("seed: {}", seed);
+
+        app.insert_resource(GameRng::new(seed));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{
+    boss::{Boss, BOSS_SCORE},
+    enemies::EnemyKind,
+    events::{DamageEvent, ScoreEvent},
+    state::AppState,
+    timestep::{on_fixed_update, FixedUpdateLabel, FixedUpdateStage},
+};
+use bevy::prelude::*;
+
+static MAX_MULTIPLIER: u32 = 8;
+
+/// Points of the current run. Every bullet hit raises the multiplier, a shot that
+/// misses halves it.
+#[derive(Resource, Default)]
+pub struct Score {
+    pub points: u32,
+    pub combo: u32,
+    missed_shot: Option<u32>,
+}
+
+impl Score {
+    pub fn multiplier(&self) -> u32 {
+        self.combo.clamp(1, MAX_MULTIPLIER)
+    }
+
+    pub fn hit(&mut self) {
+        self.combo += 1;
+    }
+
+    /// Adds `points` for a kill, scaled by the multiplier.
+    pub fn kill(&mut self, points: u32) {
+        self.points += points * self.multiplier();
+    }
+
+    /// Decays the combo for a bullet of `shot` that left the screen. The other bullets
+    /// of a shot that already missed, and of older shots, don't count again.
+    pub fn miss(&mut self, shot: u32) {
+        if self
+            .missed_shot
+            .is_some_and(|missed_shot| missed_shot >= shot)
+        {
+            return;
+        }
+
+        self.missed_shot = Some(shot);
+        self.combo /= 2;
+    }
+}
+
+fn reset_score(mut score: ResMut<Score>) {
+    *score = Score::default();
+}
+
+/// Scores the enemies and the boss killed this tick.
+fn score_kills(
+    mut damage_events: EventReader<DamageEvent>,
+    enemy_query: Query<&EnemyKind>,
+    boss_query: Query<(), With<Boss>>,
+    mut score: ResMut<Score>,
+) {
+    for damage_event in damage_events.iter() {
+        if !damage_event.fatal {
+            continue;
+        }
+
+        if let Ok(kind) = enemy_query.get(damage_event.entity) {
+            score.kill(kind.score());
+        } else if boss_query.contains(damage_event.entity) {
+            score.kill(BOSS_SCORE);
+        }
+    }
+}
+
+fn score_changed(score: Res<Score>, mut score_events: EventWriter<ScoreEvent>) {
+    if score.is_changed() {
+        score_events.send(ScoreEvent {
+            score: score.points,
+            multiplier: score.multiplier(),
+        });
+    }
+}
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Score>()
+            .add_system_set(SystemSet::on_enter(AppState::Main).with_system(reset_score))
+            .add_system_set(SystemSet::on_update(AppState::Main).with_system(score_changed))
+            .add_system_set_to_stage(
+                FixedUpdateStage,
+                on_fixed_update(AppState::Main)
+                    .after(FixedUpdateLabel::Damage)
+                    .with_system(score_kills),
+            );
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{
+    consts::{POSITION_Z, WINDOW_SIZE},
+    events::ScoreEvent,
+    score::Score,
+    state::{despawn_with, AppState, LoaderState},
+};
+use bevy::prelude::*;
+
+#[derive(Component)]
+struct ScoreText;
+
+fn add_score_text(mut commands: Commands, score: Res<Score>, loader: Res<LoaderState>) {
+    let style = TextStyle {
+        font: loader.font.clone(),
+        font_size: 16.0,
+        color: Color::WHITE,
+    };
+
+    commands
+        .spawn(Text2dBundle {
+            text: Text {
+                sections: vec![
+                    TextSection {
+                        value: "Score:".to_string(),
+                        style: style.clone(),
+                    },
+                    TextSection {
+                        value: score.points.to_string(),
+                        style: TextStyle {
+                            color: Color::GOLD,
+                            ..style.clone()
+                        },
+                    },
+                    TextSection {
+                        value: format!(" x{}", score.multiplier()),
+                        style: TextStyle {
+                            color: Color::ORANGE,
+                            ..style
+                        },
+                    },
+                ],
+                alignment: TextAlignment {
+                    horizontal: HorizontalAlign::Left,
+                    vertical: VerticalAlign::Top,
+                },
+            },
+            transform: Transform::from_xyz(
+                -WINDOW_SIZE.width / 2.0,
+                WINDOW_SIZE.height / 2.0 - 16.0 * 3.0,
+                POSITION_Z.score,
+            ),
+            ..default()
+        })
+        .insert(ScoreText);
+}
+
+fn update_score_text(
+    mut score_events: EventReader<ScoreEvent>,
+    mut query: Query<&mut Text, With<ScoreText>>,
+) {
+    for score_event in score_events.iter() {
+        for mut text in query.iter_mut() {
+            text.sections[1].value = format!("{}", score_event.score);
+            text.sections[2].value = format!(" x{}", score_event.multiplier);
+        }
+    }
+}
+
+pub struct ScoreTextPlugin;
+
+impl Plugin for ScoreTextPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_enter(AppState::Main).with_system(add_score_text))
+            .add_system_set(SystemSet::on_update(AppState::Main).with_system(update_score_text))
+            .add_system_set(
+                SystemSet::on_exit(AppState::Main).with_system(despawn_with::<ScoreText>),
+            );
+    }
+}
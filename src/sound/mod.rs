@@ -0,0 +1,44 @@
+use crate::{
+    boss::Boss,
+    events::{CharacterHitEvent, DamageEvent},
+    state::LoaderState,
+};
+use bevy::prelude::*;
+
+/// Plays a sound for every hit, an explosion for the ones that kill. The killing blow
+/// of the boss is left to its blast chain.
+fn play_hit_sounds(
+    mut damage_events: EventReader<DamageEvent>,
+    mut character_hit_events: EventReader<CharacterHitEvent>,
+    boss_query: Query<(), With<Boss>>,
+    loader: Res<LoaderState>,
+    audio: Res<Audio>,
+) {
+    let hits = damage_events
+        .iter()
+        .filter(|damage_event| !(damage_event.fatal && boss_query.contains(damage_event.entity)))
+        .map(|damage_event| damage_event.fatal)
+        .chain(
+            character_hit_events
+                .iter()
+                .map(|character_hit_event| character_hit_event.fatal),
+        );
+
+    for fatal in hits {
+        let sound = if fatal {
+            &loader.explosion_sound
+        } else {
+            &loader.collision_sound
+        };
+        audio.play(sound.clone());
+    }
+}
+
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        // Not tied to the game state, the last hit of a run ends it.
+        app.add_system(play_hit_sounds);
+    }
+}
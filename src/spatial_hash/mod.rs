@@ -0,0 +1,103 @@
+use crate::utils::{Collider, GetBoundingRect};
+use bevy::{prelude::*, utils::HashMap};
+use std::marker::PhantomData;
+
+/// Side of a grid cell, about the hit area of a drifter.
+pub static CELL_SIZE: f32 = 64.0;
+
+/// Uniform grid over the entities with a `T`, rebuilt every tick. Each entity sits in
+/// the cells its collider's bounds cover, so a collider only needs testing against the
+/// entities sharing a cell with it.
+///
+/// ```
+/// use bevy::prelude::*;
+/// use stars_rs::{spatial_hash::SpatialHash, utils::Collider};
+///
+/// fn main() {
+///     struct Item;
+///
+///     let circle = |x| Collider::Circle { center: Vec2::new(x, 0.0), radius: 2.0 };
+///     let mut grid = SpatialHash::<Item>::new(16.0);
+///     grid.insert(Entity::from_raw(0), &circle(0.0));
+///     grid.insert(Entity::from_raw(1), &circle(100.0));
+///
+///     assert_eq!(grid.query(&circle(4.0)), vec![Entity::from_raw(0)]);
+/// }
+/// ```
+#[derive(Resource)]
+pub struct SpatialHash<T> {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<Entity>>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for SpatialHash<T> {
+    fn default() -> Self {
+        Self::new(CELL_SIZE)
+    }
+}
+
+impl<T> SpatialHash<T> {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::default(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Empties the grid. Cells used since the last clear keep their memory, the others
+    /// are dropped.
+    pub fn clear(&mut self) {
+        self.cells.retain(|_, entities| {
+            let used = !entities.is_empty();
+            entities.clear();
+            used
+        });
+    }
+
+    pub fn insert(&mut self, entity: Entity, collider: &Collider) {
+        for cell in self.cells_of(collider.bounds()) {
+            self.cells.entry(cell).or_default().push(entity);
+        }
+    }
+
+    /// Entities that may hit `collider`, each once and in a stable order.
+    pub fn query(&self, collider: &Collider) -> Vec<Entity> {
+        self.query_area(collider.bounds())
+    }
+
+    /// Entities that may be in the rect from the lower left corner `min` to the upper
+    /// right one `max`, each once and in a stable order.
+    pub fn query_area(&self, (min, max): (Vec2, Vec2)) -> Vec<Entity> {
+        let mut entities: Vec<Entity> = self
+            .cells_of((min, max))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .collect();
+        entities.sort_unstable();
+        entities.dedup();
+
+        entities
+    }
+
+    fn cells_of(&self, (min, max): (Vec2, Vec2)) -> impl Iterator<Item = IVec2> {
+        let min = (min / self.cell_size).floor().as_ivec2();
+        let max = (max / self.cell_size).floor().as_ivec2();
+
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+    }
+}
+
+/// Refills the grid with where every `T` is this tick.
+pub fn rebuild<T: Component + GetBoundingRect>(
+    mut grid: ResMut<SpatialHash<T>>,
+    query: Query<(Entity, &T)>,
+) {
+    grid.clear();
+
+    for (entity, item) in query.iter() {
+        grid.insert(entity, &item.get_collider());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::config::GameConfig;
 use bevy::{asset::HandleId, prelude::*};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -5,9 +6,15 @@ pub enum AppState {
     Loading,
     Main,
     Menu,
+    Paused,
+    GameOver,
+    Victory,
+    HighScores,
+    Controls,
 }
 
-#[derive(Resource)]
+/// Handles of every loaded asset. `Default` gives placeholder handles for headless runs.
+#[derive(Resource, Default)]
 pub struct LoaderState {
     pub background_image: Handle<Image>,
     pub enemy_image: Handle<Image>,
@@ -19,6 +26,7 @@ pub struct LoaderState {
     pub collision_sound: Handle<AudioSource>,
     pub explosion_sound: Handle<AudioSource>,
     pub font: Handle<Font>,
+    pub config: Handle<GameConfig>,
 }
 impl LoaderState {
     pub fn ids(&self) -> Vec<HandleId> {
@@ -36,6 +44,13 @@ impl LoaderState {
     }
 }
 
+/// Despawns every entity with `T`, used to clear a run when leaving `AppState::Main`.
+pub fn despawn_with<T: Component>(mut commands: Commands, query: Query<Entity, With<T>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
 pub struct StatePlugin;
 
 impl Plugin for StatePlugin {
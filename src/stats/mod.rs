@@ -0,0 +1,40 @@
+use crate::state::AppState;
+use bevy::prelude::*;
+
+/// Numbers of the current run, shown on the results screen.
+#[derive(Resource, Default)]
+pub struct Stats {
+    pub time: f32,
+    pub shots: u32,
+    pub hits: u32,
+    pub wave: u32,
+}
+
+impl Stats {
+    /// Share of shots that hit an enemy, from 0.0 to 1.0.
+    pub fn accuracy(&self) -> f32 {
+        if self.shots == 0 {
+            0.0
+        } else {
+            (self.hits as f32 / self.shots as f32).min(1.0)
+        }
+    }
+}
+
+fn reset_stats(mut stats: ResMut<Stats>) {
+    *stats = Stats::default();
+}
+
+fn update_time(time: Res<Time>, mut stats: ResMut<Stats>) {
+    stats.time += time.delta_seconds();
+}
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Stats>()
+            .add_system_set(SystemSet::on_enter(AppState::Main).with_system(reset_stats))
+            .add_system_set(SystemSet::on_update(AppState::Main).with_system(update_time));
+    }
+}
@@ -0,0 +1,168 @@
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+#[cfg(not(target_arch = "wasm32"))]
+use std::{fs, path::PathBuf};
+
+/// Where player data is kept between sessions: RON files in a directory under the
+/// user's data dir on native, `localStorage` on wasm. `Memory` keeps values only as
+/// long as the app runs, used by headless runs.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub enum Storage {
+    #[cfg(not(target_arch = "wasm32"))]
+    File(PathBuf),
+    #[cfg(target_arch = "wasm32")]
+    LocalStorage,
+    Memory(MemoryStorage),
+}
+
+/// Values of `Storage::Memory` by name. Clones share the values, so an app built with
+/// a clone sees what the other one saved.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStorage(Arc<Mutex<HashMap<String, String>>>);
+
+impl MemoryStorage {
+    fn get(&self, name: &str) -> Option<String> {
+        self.0.lock().unwrap().get(name).cloned()
+    }
+
+    fn set(&self, name: &str, value: &str) {
+        self.0
+            .lock()
+            .unwrap()
+            .insert(name.to_string(), value.to_string());
+    }
+}
+
+impl PartialEq for MemoryStorage {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for MemoryStorage {}
+
+impl Default for Storage {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn default() -> Self {
+        data_dir()
+            .map(|dir| Storage::File(dir.join("stars_rs")))
+            .unwrap_or_else(Storage::memory)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn default() -> Self {
+        Storage::LocalStorage
+    }
+}
+
+impl Storage {
+    /// Empty `Memory` storage.
+    pub fn memory() -> Self {
+        Storage::Memory(MemoryStorage::default())
+    }
+
+    /// Reads the value stored as `name`, a missing or broken one gives the default.
+    pub fn load<T: DeserializeOwned + Default>(&self, name: &str) -> T {
+        let Some(value) = self.read(name) else {
+            return T::default();
+        };
+
+        ron::from_str(&value).unwrap_or_else(|error| {
+            warn!("Ignoring broken {}: {}", name, error);
+            T::default()
+        })
+    }
+
+    pub fn save<T: Serialize>(&self, name: &str, value: &T) {
+        match ron::to_string(value) {
+            Ok(value) => self.write(name, &value),
+            Err(error) => warn!("Can't serialize {}: {}", name, error),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn path(dir: &std::path::Path, name: &str) -> PathBuf {
+        dir.join(format!("{}.ron", name))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn read(&self, name: &str) -> Option<String> {
+        match self {
+            Storage::File(dir) => fs::read_to_string(Storage::path(dir, name)).ok(),
+            Storage::Memory(values) => values.get(name),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn write(&self, name: &str, value: &str) {
+        match self {
+            Storage::File(dir) => {
+                let path = Storage::path(dir, name);
+                let result = fs::create_dir_all(dir).and_then(|_| fs::write(&path, value));
+
+                if let Err(error) = result {
+                    warn!("Can't save {} to {}: {}", name, path.display(), error);
+                }
+            }
+            Storage::Memory(values) => values.set(name, value),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn read(&self, name: &str) -> Option<String> {
+        match self {
+            Storage::LocalStorage => local_storage()?.get_item(&storage_key(name)).ok()?,
+            Storage::Memory(values) => values.get(name),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn write(&self, name: &str, value: &str) {
+        match self {
+            Storage::LocalStorage => {
+                let saved = local_storage()
+                    .is_some_and(|storage| storage.set_item(&storage_key(name), value).is_ok());
+
+                if !saved {
+                    warn!("Can't save {} to localStorage", name);
+                }
+            }
+            Storage::Memory(values) => values.set(name, value),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn data_dir() -> Option<PathBuf> {
+    let env_dir = |name: &str| std::env::var_os(name).map(PathBuf::from);
+
+    if cfg!(windows) {
+        env_dir("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        env_dir("HOME").map(|home| home.join("Library").join("Application Support"))
+    } else {
+        env_dir("XDG_DATA_HOME").or_else(|| env_dir("HOME").map(|home| home.join(".local/share")))
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn storage_key(name: &str) -> String {
+    format!("stars_rs_{}", name)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+pub struct StoragePlugin;
+
+impl Plugin for StoragePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Storage>();
+    }
+}
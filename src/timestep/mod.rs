@@ -0,0 +1,68 @@
+use crate::{state::AppState, utils::GetPosition};
+use bevy::{
+    ecs::schedule::ShouldRun,
+    prelude::*,
+    time::{FixedTimestep, FixedTimesteps},
+};
+
+/// Duration of one gameplay tick in seconds.
+pub static TIMESTEP: f64 = 1.0 / 60.0;
+static FIXED_TIMESTEP: &str = "fixed_timestep";
+
+/// Stage running movement and collision systems at [`TIMESTEP`], before `CoreStage::Update`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, StageLabel)]
+pub struct FixedUpdateStage;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemLabel)]
+pub enum FixedUpdateLabel {
+    Movement,
+    Collision,
+    /// Systems applying the damage of the collisions found this tick.
+    Damage,
+}
+
+/// Counterpart of `SystemSet::on_update` for [`FixedUpdateStage`], which has no state driver
+/// to end the looping run criteria of `on_update`.
+pub fn on_fixed_update(app_state: AppState) -> SystemSet {
+    SystemSet::new().with_run_criteria(move |state: Res<State<AppState>>| {
+        if state.current() == &app_state {
+            ShouldRun::Yes
+        } else {
+            ShouldRun::No
+        }
+    })
+}
+
+/// Position at the start of the last tick, `Transform` is interpolated from it.
+#[derive(Component, Deref, DerefMut)]
+pub struct PreviousPosition(pub Vec2);
+
+/// Moves `Transform` between the previous and the current tick position of `T`.
+pub fn interpolate<T: Component + GetPosition>(
+    fixed_timesteps: Res<FixedTimesteps>,
+    mut query: Query<(&T, &PreviousPosition, &mut Transform)>,
+) {
+    let alpha = fixed_timesteps
+        .get(FIXED_TIMESTEP)
+        .map_or(1.0, |state| state.overstep_percentage() as f32);
+
+    for (item, previous_position, mut transform) in query.iter_mut() {
+        let position = previous_position.lerp(item.get_position(), alpha.min(1.0));
+
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
+
+pub struct TimestepPlugin;
+
+impl Plugin for TimestepPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_stage_before(
+            CoreStage::Update,
+            FixedUpdateStage,
+            SystemStage::parallel()
+                .with_run_criteria(FixedTimestep::step(TIMESTEP).with_label(FIXED_TIMESTEP)),
+        );
+    }
+}
@@ -0,0 +1,363 @@
+use crate::{
+    consts::{POSITION_Z, WINDOW_SIZE},
+    state::{despawn_with, AppState},
+};
+use bevy::{
+    input::{
+        touch::{Touch, Touches},
+        InputSystem,
+    },
+    prelude::*,
+};
+
+/// Radius of a stick's base, the knob is fully deflected at its rim.
+pub static STICK_RADIUS: f32 = 50.0;
+/// A touch starting this far from a stick's centre still grabs it.
+static STICK_GRAB_RADIUS: f32 = 80.0;
+/// Deflection below this is a resting thumb and ignored.
+static STICK_DEADZONE: f32 = 0.2;
+pub static FIRE_BUTTON_RADIUS: f32 = 35.0;
+
+/// Layout of the on-screen controls, in world coordinates.
+pub const MOVE_STICK_CENTER: Vec2 = Vec2::new(-210.0, -160.0);
+pub const AIM_STICK_CENTER: Vec2 = Vec2::new(210.0, -160.0);
+pub const FIRE_BUTTON_CENTER: Vec2 = Vec2::new(150.0, -60.0);
+
+const CONTROL_COLOR: Color = Color::rgba(1.0, 1.0, 1.0, 0.15);
+const ACTIVE_CONTROL_COLOR: Color = Color::rgba(1.0, 1.0, 1.0, 0.35);
+
+/// Turns a touch position, measured from the top left of the window, into world
+/// coordinates.
+pub fn touch_to_world(position: Vec2) -> Vec2 {
+    Vec2::new(
+        position.x - WINDOW_SIZE.width / 2.0,
+        WINDOW_SIZE.height / 2.0 - position.y,
+    )
+}
+
+/// Virtual joystick held by at most one touch.
+#[derive(Debug)]
+struct VirtualStick {
+    center: Vec2,
+    touch: Option<u64>,
+    deflection: Vec2,
+}
+
+impl VirtualStick {
+    fn new(center: Vec2) -> Self {
+        Self {
+            center,
+            touch: None,
+            deflection: Vec2::ZERO,
+        }
+    }
+
+    fn grab(&mut self, touch: &Touch) -> bool {
+        let grabbed = self.touch.is_none()
+            && touch_to_world(touch.position()).distance(self.center) <= STICK_GRAB_RADIUS;
+        if grabbed {
+            self.touch = Some(touch.id());
+        }
+
+        grabbed
+    }
+
+    fn update(&mut self, touches: &Touches) {
+        let Some(touch) = self.touch.and_then(|id| touches.get_pressed(id)) else {
+            self.touch = None;
+            self.deflection = Vec2::ZERO;
+            return;
+        };
+
+        let deflection =
+            ((touch_to_world(touch.position()) - self.center) / STICK_RADIUS).clamp_length_max(1.0);
+        self.deflection = if deflection.length() > STICK_DEADZONE {
+            deflection
+        } else {
+            Vec2::ZERO
+        };
+    }
+}
+
+/// Virtual button held by at most one touch.
+#[derive(Debug)]
+struct VirtualButton {
+    center: Vec2,
+    touch: Option<u64>,
+    just_pressed: bool,
+}
+
+impl VirtualButton {
+    fn new(center: Vec2) -> Self {
+        Self {
+            center,
+            touch: None,
+            just_pressed: false,
+        }
+    }
+
+    fn grab(&mut self, touch: &Touch) -> bool {
+        let grabbed = self.touch.is_none()
+            && touch_to_world(touch.position()).distance(self.center) <= FIRE_BUTTON_RADIUS;
+        if grabbed {
+            self.touch = Some(touch.id());
+            self.just_pressed = true;
+        }
+
+        grabbed
+    }
+
+    fn update(&mut self, touches: &Touches) {
+        if self
+            .touch
+            .is_some_and(|id| touches.get_pressed(id).is_none())
+        {
+            self.touch = None;
+        }
+    }
+}
+
+/// On-screen sticks and fire button, read by `ActionInput` next to the keyboard and
+/// gamepads. They turn on with the first touch and stay on.
+#[derive(Resource, Debug)]
+pub struct TouchControls {
+    pub enabled: bool,
+    move_stick: VirtualStick,
+    aim_stick: VirtualStick,
+    fire_button: VirtualButton,
+}
+
+impl Default for TouchControls {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            move_stick: VirtualStick::new(MOVE_STICK_CENTER),
+            aim_stick: VirtualStick::new(AIM_STICK_CENTER),
+            fire_button: VirtualButton::new(FIRE_BUTTON_CENTER),
+        }
+    }
+}
+
+impl TouchControls {
+    /// Deflection of the move stick, at most 1 long.
+    pub fn move_stick(&self) -> Vec2 {
+        self.move_stick.deflection
+    }
+
+    /// Deflection of the aim stick, at most 1 long.
+    pub fn aim_stick(&self) -> Vec2 {
+        self.aim_stick.deflection
+    }
+
+    pub fn fire_pressed(&self) -> bool {
+        self.fire_button.touch.is_some()
+    }
+
+    pub fn fire_just_pressed(&self) -> bool {
+        self.fire_button.just_pressed
+    }
+}
+
+#[derive(Component)]
+struct TouchControl;
+
+/// Knob of the move or the aim stick.
+#[derive(Component)]
+enum Knob {
+    Move,
+    Aim,
+}
+
+#[derive(Component)]
+struct FireButton;
+
+/// Hands new touches to the control under them and follows the ones held.
+fn read_touches(touches: Res<Touches>, mut controls: ResMut<TouchControls>) {
+    let controls = controls.as_mut();
+    controls.fire_button.just_pressed = false;
+
+    for touch in touches.iter_just_pressed() {
+        controls.enabled = true;
+        if !controls.fire_button.grab(touch) && !controls.move_stick.grab(touch) {
+            controls.aim_stick.grab(touch);
+        }
+    }
+
+    controls.move_stick.update(&touches);
+    controls.aim_stick.update(&touches);
+    controls.fire_button.update(&touches);
+}
+
+fn spawn_control(commands: &mut Commands, position: Vec2, z: f32, size: f32) -> Entity {
+    commands
+        .spawn(SpriteBundle {
+            sprite: Sprite {
+                color: CONTROL_COLOR,
+                custom_size: Some(Vec2::splat(size)),
+                ..default()
+            },
+            transform: Transform::from_xyz(position.x, position.y, z),
+            ..default()
+        })
+        .insert(TouchControl)
+        .id()
+}
+
+/// Puts the controls on screen once touch input has been seen.
+fn show_controls(
+    controls: Res<TouchControls>,
+    query: Query<(), With<TouchControl>>,
+    mut commands: Commands,
+) {
+    if !controls.enabled || !query.is_empty() {
+        return;
+    }
+
+    let z = POSITION_Z.touch;
+    for (center, knob) in [
+        (MOVE_STICK_CENTER, Knob::Move),
+        (AIM_STICK_CENTER, Knob::Aim),
+    ] {
+        spawn_control(&mut commands, center, z, STICK_RADIUS * 2.0);
+        let knob_entity = spawn_control(&mut commands, center, z + 0.1, STICK_RADIUS * 0.8);
+        commands.entity(knob_entity).insert(knob);
+    }
+
+    let fire_button = spawn_control(
+        &mut commands,
+        FIRE_BUTTON_CENTER,
+        z,
+        FIRE_BUTTON_RADIUS * 2.0,
+    );
+    commands.entity(fire_button).insert(FireButton);
+}
+
+fn update_controls(
+    controls: Res<TouchControls>,
+    mut knob_query: Query<(&Knob, &mut Transform)>,
+    mut fire_query: Query<&mut Sprite, With<FireButton>>,
+) {
+    for (knob, mut transform) in knob_query.iter_mut() {
+        let position = match knob {
+            Knob::Move => MOVE_STICK_CENTER + controls.move_stick() * STICK_RADIUS,
+            Knob::Aim => AIM_STICK_CENTER + controls.aim_stick() * STICK_RADIUS,
+        };
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+
+    for mut sprite in fire_query.iter_mut() {
+        sprite.color = if controls.fire_pressed() {
+            ACTIVE_CONTROL_COLOR
+        } else {
+            CONTROL_COLOR
+        };
+    }
+}
+
+/// winit doesn't report touches on the web, so they are taken from the canvas and
+/// sent as Bevy `TouchInput` events.
+#[cfg(target_arch = "wasm32")]
+mod web {
+    use crate::consts::WINDOW_SIZE;
+    use bevy::{
+        input::touch::{TouchInput, TouchPhase},
+        prelude::*,
+    };
+    use std::cell::RefCell;
+    use wasm_bindgen::{closure::Closure, JsCast};
+
+    thread_local! {
+        /// Touches reported by the browser since the last frame.
+        static QUEUE: RefCell<Vec<TouchInput>> = RefCell::new(Vec::new());
+    }
+
+    pub fn listen() {
+        let Some(canvas) = web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.query_selector("#bevy").ok().flatten())
+        else {
+            warn!("No canvas to take touches from");
+            return;
+        };
+
+        // Not passive, so that handled touches don't also scroll or click.
+        let options = web_sys::AddEventListenerOptions::new();
+        options.set_passive(false);
+
+        for (event_type, phase) in [
+            ("touchstart", TouchPhase::Started),
+            ("touchmove", TouchPhase::Moved),
+            ("touchend", TouchPhase::Ended),
+            ("touchcancel", TouchPhase::Cancelled),
+        ] {
+            let target = canvas.clone();
+            let listener = Closure::<dyn FnMut(web_sys::TouchEvent)>::new(
+                move |event: web_sys::TouchEvent| {
+                    event.prevent_default();
+
+                    // CSS may scale the canvas, positions are in game pixels.
+                    let rect = target.get_bounding_client_rect();
+                    let scale = Vec2::new(
+                        WINDOW_SIZE.width / rect.width() as f32,
+                        WINDOW_SIZE.height / rect.height() as f32,
+                    );
+                    let touches = event.changed_touches();
+
+                    QUEUE.with(|queue| {
+                        let mut queue = queue.borrow_mut();
+                        for touch in (0..touches.length()).filter_map(|index| touches.get(index)) {
+                            let position = Vec2::new(
+                                touch.client_x() as f32 - rect.left() as f32,
+                                touch.client_y() as f32 - rect.top() as f32,
+                            );
+                            queue.push(TouchInput {
+                                phase,
+                                position: position * scale,
+                                force: None,
+                                id: touch.identifier() as u64,
+                            });
+                        }
+                    });
+                },
+            );
+
+            let added = canvas.add_event_listener_with_callback_and_add_event_listener_options(
+                event_type,
+                listener.as_ref().unchecked_ref(),
+                &options,
+            );
+            if added.is_err() {
+                warn!("Can't listen for {} on the canvas", event_type);
+            }
+            listener.forget();
+        }
+    }
+
+    pub fn forward_touches(mut touch_events: EventWriter<TouchInput>) {
+        QUEUE.with(|queue| touch_events.send_batch(queue.borrow_mut().drain(..)));
+    }
+}
+
+pub struct TouchPlugin;
+
+impl Plugin for TouchPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TouchControls>()
+            .add_system_to_stage(CoreStage::PreUpdate, read_touches.after(InputSystem))
+            .add_system_set(
+                SystemSet::on_update(AppState::Main)
+                    .with_system(show_controls)
+                    .with_system(update_controls),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Main).with_system(despawn_with::<TouchControl>),
+            );
+
+        #[cfg(target_arch = "wasm32")]
+        app.add_startup_system(web::listen).add_system_to_stage(
+            CoreStage::PreUpdate,
+            web::forward_touches.before(InputSystem),
+        );
+    }
+}
@@ -1,5 +1,8 @@
 use bevy::math::Vec2;
-use rand::random;
+use rand::Rng;
+
+/// Halvings searching for the first touch of a sweep, a pixel over more than 60000.
+static SWEEP_STEPS: u32 = 16;
 
 /// ```
 /// use stars_rs::utils::Position;
@@ -39,6 +42,312 @@ pub struct BoundingRect {
     pub height: f32,
 }
 
+/// Shape an entity collides with, in world coordinates. Shapes are tested against each
+/// other with separating axes, rotation included.
+///
+/// ```
+/// use bevy::math::Vec2;
+/// use stars_rs::utils::Collider;
+///
+/// fn main() {
+///     let circle = Collider::Circle { center: Vec2::new(3.0, 0.0), radius: 1.0 };
+///     let rect = Collider::OrientedBox {
+///         center: Vec2::ZERO,
+///         half_size: Vec2::new(2.5, 0.5),
+///         rotation: std::f32::consts::FRAC_PI_2,
+///     };
+///
+///     assert!(!rect.intersects(&circle));
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Collider {
+    Circle {
+        center: Vec2,
+        radius: f32,
+    },
+    /// Rectangle turned by `rotation` radians around its centre, as a sprite is by its
+    /// `Transform`.
+    OrientedBox {
+        center: Vec2,
+        half_size: Vec2,
+        rotation: f32,
+    },
+    /// Circle of `radius` swept from `start` to `end`.
+    Capsule {
+        start: Vec2,
+        end: Vec2,
+        radius: f32,
+    },
+}
+
+impl From<BoundingRect> for Collider {
+    /// Unrotated box over the area [`hit_test`] checks, half the size of the rect.
+    fn from(rect: BoundingRect) -> Self {
+        Collider::OrientedBox {
+            center: Vec2::new(rect.x, rect.y),
+            half_size: Vec2::new(rect.width, rect.height) / 4.0,
+            rotation: 0.0,
+        }
+    }
+}
+
+impl Collider {
+    /// Whether the shapes overlap, touching ones don't.
+    pub fn intersects(&self, other: &Collider) -> bool {
+        overlap(
+            &self.vertices(),
+            &other.vertices(),
+            self.radius() + other.radius(),
+        )
+    }
+
+    /// Share of `motion` the shape travels before it first touches `other`, `None` when
+    /// it gets by. Fast shapes test the whole path they cover in a tick with it, not
+    /// just where they end up.
+    ///
+    /// ```
+    /// use bevy::math::Vec2;
+    /// use stars_rs::utils::Collider;
+    ///
+    /// fn main() {
+    ///     let bullet = Collider::Circle { center: Vec2::ZERO, radius: 1.0 };
+    ///     let wall = Collider::Circle { center: Vec2::new(50.0, 0.0), radius: 1.0 };
+    ///
+    ///     let share = bullet.sweep(Vec2::new(100.0, 0.0), &wall).unwrap();
+    ///     assert!((share - 0.48).abs() < 0.001);
+    ///     assert_eq!(bullet.sweep(Vec2::new(0.0, 100.0), &wall), None);
+    /// }
+    /// ```
+    pub fn sweep(&self, motion: Vec2, other: &Collider) -> Option<f32> {
+        let vertices = self.vertices();
+        let others = other.vertices();
+        let radius = self.radius() + other.radius();
+        let swept = |share: f32| {
+            convex_hull(
+                vertices
+                    .iter()
+                    .flat_map(|vertex| [*vertex, *vertex + motion * share])
+                    .collect(),
+            )
+        };
+
+        if !overlap(&swept(1.0), &others, radius) {
+            return None;
+        }
+
+        // The path grows with the share, so the first touch can be bisected for.
+        let (mut low, mut high) = (0.0, 1.0);
+        for _ in 0..SWEEP_STEPS {
+            let share = (low + high) / 2.0;
+            if overlap(&swept(share), &others, radius) {
+                high = share;
+            } else {
+                low = share;
+            }
+        }
+
+        Some(if overlap(&vertices, &others, radius) {
+            0.0
+        } else {
+            high
+        })
+    }
+
+    /// The shape moved by `offset`.
+    pub fn translated(&self, offset: Vec2) -> Collider {
+        match *self {
+            Collider::Circle { center, radius } => Collider::Circle {
+                center: center + offset,
+                radius,
+            },
+            Collider::OrientedBox {
+                center,
+                half_size,
+                rotation,
+            } => Collider::OrientedBox {
+                center: center + offset,
+                half_size,
+                rotation,
+            },
+            Collider::Capsule { start, end, radius } => Collider::Capsule {
+                start: start + offset,
+                end: end + offset,
+                radius,
+            },
+        }
+    }
+
+    /// Lower left and upper right corners of the smallest unrotated rect around the
+    /// shape and everywhere it passes moving by `motion`.
+    pub fn swept_bounds(&self, motion: Vec2) -> (Vec2, Vec2) {
+        let (min, max) = self.bounds();
+
+        (min.min(min + motion), max.max(max + motion))
+    }
+
+    /// Lower left and upper right corners of the smallest unrotated rect around the
+    /// shape.
+    ///
+    /// ```
+    /// use bevy::math::Vec2;
+    /// use stars_rs::utils::Collider;
+    ///
+    /// fn main() {
+    ///     let capsule = Collider::Capsule { start: Vec2::ZERO, end: Vec2::X, radius: 1.0 };
+    ///     assert_eq!(capsule.bounds(), (Vec2::new(-1.0, -1.0), Vec2::new(2.0, 1.0)));
+    /// }
+    /// ```
+    pub fn bounds(&self) -> (Vec2, Vec2) {
+        let vertices = self.vertices();
+        let min = vertices
+            .iter()
+            .copied()
+            .fold(Vec2::splat(f32::MAX), Vec2::min);
+        let max = vertices
+            .iter()
+            .copied()
+            .fold(Vec2::splat(f32::MIN), Vec2::max);
+
+        (min - self.radius(), max + self.radius())
+    }
+
+    /// Corners of the shape without its rounding, counterclockwise: the centre of a
+    /// circle, the segment of a capsule.
+    fn vertices(&self) -> Vec<Vec2> {
+        match *self {
+            Collider::Circle { center, .. } => vec![center],
+            Collider::OrientedBox {
+                center,
+                half_size,
+                rotation,
+            } => {
+                let x = Vec2::from_angle(rotation) * half_size.x;
+                let y = Vec2::from_angle(rotation).perp() * half_size.y;
+
+                vec![
+                    center - x - y,
+                    center + x - y,
+                    center + x + y,
+                    center - x + y,
+                ]
+            }
+            Collider::Capsule { start, end, .. } => vec![start, end],
+        }
+    }
+
+    fn radius(&self) -> f32 {
+        match *self {
+            Collider::Circle { radius, .. } | Collider::Capsule { radius, .. } => radius,
+            Collider::OrientedBox { .. } => 0.0,
+        }
+    }
+}
+
+/// Whether the polygons through `lhs` and `rhs`, rounded by `radius` together, overlap.
+fn overlap(lhs: &[Vec2], rhs: &[Vec2], radius: f32) -> bool {
+    // Rounded shapes can also be parted along the line between a corner of one and
+    // the closest point of the other.
+    let corner_axes = lhs
+        .iter()
+        .map(|vertex| closest_point(rhs, *vertex) - *vertex)
+        .chain(
+            rhs.iter()
+                .map(|vertex| closest_point(lhs, *vertex) - *vertex),
+        );
+
+    let separated = edge_normals(lhs)
+        .chain(edge_normals(rhs))
+        .chain(corner_axes)
+        .filter_map(Vec2::try_normalize)
+        .any(|axis| {
+            let (lhs_min, lhs_max) = project(lhs, axis);
+            let (rhs_min, rhs_max) = project(rhs, axis);
+
+            lhs_min >= rhs_max + radius || rhs_min >= lhs_max + radius
+        });
+
+    !separated
+}
+
+/// Smallest convex polygon around `points`, counterclockwise. Points in a line give
+/// its two ends.
+fn convex_hull(mut points: Vec<Vec2>) -> Vec<Vec2> {
+    points.sort_by(|lhs, rhs| lhs.x.total_cmp(&rhs.x).then(lhs.y.total_cmp(&rhs.y)));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    let half_hull = |points: &mut dyn Iterator<Item = Vec2>| {
+        let mut hull: Vec<Vec2> = Vec::new();
+        for point in points {
+            while let [.., previous, last] = hull[..] {
+                if (last - previous).perp_dot(point - previous) > 0.0 {
+                    break;
+                }
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        hull.pop();
+        hull
+    };
+
+    let mut hull = half_hull(&mut points.iter().copied());
+    hull.extend(half_hull(&mut points.iter().rev().copied()));
+    hull
+}
+
+/// Sides of the polygon through `vertices`, a lone point is a side of its own.
+fn edges(vertices: &[Vec2]) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+    let count = match vertices.len() {
+        1 | 2 => 1,
+        count => count,
+    };
+
+    (0..count).map(|index| (vertices[index], vertices[(index + 1) % vertices.len()]))
+}
+
+fn edge_normals(vertices: &[Vec2]) -> impl Iterator<Item = Vec2> + '_ {
+    edges(vertices).map(|(start, end)| (end - start).perp())
+}
+
+/// Point of the polygon through `vertices` closest to `point`, `point` itself when the
+/// polygon holds it.
+fn closest_point(vertices: &[Vec2], point: Vec2) -> Vec2 {
+    let inside = vertices.len() > 2
+        && edges(vertices).all(|(start, end)| (end - start).perp_dot(point - start) >= 0.0);
+    if inside {
+        return point;
+    }
+
+    edges(vertices)
+        .map(|(start, end)| {
+            let edge = end - start;
+            let length_squared = edge.length_squared();
+            if length_squared == 0.0 {
+                return start;
+            }
+
+            start + edge * ((point - start).dot(edge) / length_squared).clamp(0.0, 1.0)
+        })
+        .min_by(|lhs, rhs| {
+            lhs.distance_squared(point)
+                .total_cmp(&rhs.distance_squared(point))
+        })
+        .unwrap_or(point)
+}
+
+fn project(vertices: &[Vec2], axis: Vec2) -> (f32, f32) {
+    vertices
+        .iter()
+        .map(|vertex| vertex.dot(axis))
+        .fold((f32::MAX, f32::MIN), |(min, max), distance| {
+            (min.min(distance), max.max(distance))
+        })
+}
+
 /// ```
 /// use stars_rs::utils::{BoundingRect, GetBoundingRect};
 ///
@@ -55,6 +364,32 @@ pub struct BoundingRect {
 ///
 pub trait GetBoundingRect {
     fn get_bounding_rect(&self) -> BoundingRect;
+
+    /// Shape hits are tested with, the hit area of the bounding rect unless the shape
+    /// or its rotation matter.
+    fn get_collider(&self) -> Collider {
+        self.get_bounding_rect().into()
+    }
+}
+
+/// ```
+/// use stars_rs::utils::{GetPosition};
+/// use bevy::math::{Vec2};
+///
+/// fn main() {
+///     struct Item {
+///         position: Vec2,
+///     };
+///
+///     impl GetPosition for Item {
+///         fn get_position(&self) -> Vec2 {
+///             self.position
+///         }
+///     }
+/// }
+/// ```
+pub trait GetPosition {
+    fn get_position(&self) -> Vec2;
 }
 
 /// ```
@@ -121,28 +456,54 @@ pub fn hit_test(lhr: BoundingRect, rhr: BoundingRect) -> bool {
 /// use stars_rs::utils::random_in_range;
 ///
 /// fn main() {
-///     let x = random_in_range(0.0, 1.0);
+///     let x = random_in_range(&mut rand::thread_rng(), 0.0, 1.0);
 /// }
 /// ```
-pub fn random_in_range(min: f32, max: f32) -> f32 {
-    min + (max - min) * random::<f32>()
+pub fn random_in_range<R: Rng + ?Sized>(rng: &mut R, min: f32, max: f32) -> f32 {
+    min + (max - min) * rng.gen::<f32>()
 }
 
 /// ```
 /// use stars_rs::utils::random_in_rect_edge;
 ///
 /// fn main() {
-///     let x = random_in_rect_edge(-1.0, 1.0, 1.0, -1.0);
+///     let x = random_in_rect_edge(&mut rand::thread_rng(), -1.0, 1.0, 1.0, -1.0);
 /// }
 /// ```
-pub fn random_in_rect_edge(left: f32, right: f32, top: f32, bottom: f32) -> Vec2 {
-    if random::<bool>() {
-        let x = random_in_range(left, right);
-        let y = if random::<bool>() { top } else { bottom };
+pub fn random_in_rect_edge<R: Rng + ?Sized>(
+    rng: &mut R,
+    left: f32,
+    right: f32,
+    top: f32,
+    bottom: f32,
+) -> Vec2 {
+    if rng.gen::<bool>() {
+        let x = random_in_range(rng, left, right);
+        let y = if rng.gen::<bool>() { top } else { bottom };
         Vec2::new(x, y)
     } else {
-        let x = if random::<bool>() { right } else { left };
-        let y = random_in_range(bottom, top);
+        let x = if rng.gen::<bool>() { right } else { left };
+        let y = random_in_range(rng, bottom, top);
         Vec2::new(x, y)
     }
 }
+
+/// Turns `speed` toward `target` by the `turn` share of the way, without changing its
+/// length.
+///
+/// ```
+/// use stars_rs::utils::steer;
+/// use bevy::math::Vec2;
+///
+/// fn main() {
+///     let speed = steer(Vec2::ZERO, Vec2::new(0.0, 1.0), Vec2::new(1.0, 0.0), 0.1);
+/// }
+/// ```
+pub fn steer(position: Vec2, speed: Vec2, target: Vec2, turn: f32) -> Vec2 {
+    let desired = (target - position).normalize_or_zero() * speed.length();
+
+    speed
+        .lerp(desired, turn.clamp(0.0, 1.0))
+        .normalize_or_zero()
+        * speed.length()
+}
@@ -0,0 +1,69 @@
+use crate::{
+    consts::POSITION_Z,
+    events::WaveEvent,
+    state::{despawn_with, AppState, LoaderState},
+};
+use bevy::prelude::*;
+
+static WAVE_TEXT_DURATION: f32 = 2.0;
+
+#[derive(Component, Deref, DerefMut)]
+struct WaveText(Timer);
+
+fn add_wave_text(
+    mut wave_events: EventReader<WaveEvent>,
+    mut commands: Commands,
+    loader: Res<LoaderState>,
+    query: Query<Entity, With<WaveText>>,
+) {
+    for wave_event in wave_events.iter() {
+        for entity in query.iter() {
+            commands.entity(entity).despawn();
+        }
+
+        commands
+            .spawn(Text2dBundle {
+                text: Text::from_section(
+                    format!("Wave {}", wave_event.wave),
+                    TextStyle {
+                        font: loader.font.clone(),
+                        font_size: 40.0,
+                        color: Color::GOLD,
+                    },
+                )
+                .with_alignment(TextAlignment::CENTER),
+                transform: Transform::from_xyz(0.0, 0.0, POSITION_Z.wave),
+                ..default()
+            })
+            .insert(WaveText(Timer::from_seconds(
+                WAVE_TEXT_DURATION,
+                TimerMode::Once,
+            )));
+    }
+}
+
+fn update_wave_text(
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut WaveText, &mut Text)>,
+    mut commands: Commands,
+) {
+    for (entity, mut timer, mut text) in query.iter_mut() {
+        if timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        } else {
+            text.sections[0].style.color.set_a(timer.percent_left());
+        }
+    }
+}
+
+pub struct WaveTextPlugin;
+
+impl Plugin for WaveTextPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_update(AppState::Main).with_system(add_wave_text))
+            .add_system_set(SystemSet::on_update(AppState::Main).with_system(update_wave_text))
+            .add_system_set(
+                SystemSet::on_exit(AppState::Main).with_system(despawn_with::<WaveText>),
+            );
+    }
+}
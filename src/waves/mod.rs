@@ -0,0 +1,141 @@
+use crate::{
+    boss::add_boss,
+    config::{BossConfig, GameConfig, WavesConfig},
+    enemies::{add_enemies, EnemyCount, EnemyTextureAtlas},
+    events::{EnemiesLeftEvent, WaveEvent},
+    rng::GameRng,
+    state::{despawn_with, AppState},
+    stats::Stats,
+};
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct Wave {
+    pub number: u32,
+    pending: bool,
+    delay: Timer,
+    /// Set once the boss wave is cleared, the boss spawns after the wave delay.
+    pub boss_due: bool,
+    pub boss_defeated: bool,
+}
+
+impl Wave {
+    pub fn enemy_count(&self, config: &WavesConfig) -> u32 {
+        (config.first_enemy_count + config.enemy_increment * self.number.saturating_sub(1))
+            .min(config.max_enemy_count)
+    }
+
+    pub fn speed_scale(&self, config: &WavesConfig) -> f32 {
+        (1.0 + config.speed_increment * self.number.saturating_sub(1) as f32)
+            .min(config.max_speed_scale)
+    }
+
+    /// Whether the boss still has to be beaten before this wave counts as cleared.
+    pub fn boss_ahead(&self, config: &BossConfig) -> bool {
+        self.number == config.wave && !self.boss_defeated
+    }
+}
+
+impl Default for Wave {
+    fn default() -> Self {
+        Self {
+            number: 0,
+            pending: true,
+            delay: Timer::from_seconds(0.0, TimerMode::Once),
+            boss_due: false,
+            boss_defeated: false,
+        }
+    }
+}
+
+fn setup_wave(mut commands: Commands) {
+    commands.spawn_empty().insert(Wave::default());
+}
+
+fn handle_enemies_left(
+    mut enemies_left_events: EventReader<EnemiesLeftEvent>,
+    config: Res<GameConfig>,
+    mut query: Query<&mut Wave>,
+) {
+    for enemies_left_event in enemies_left_events.iter() {
+        let mut wave = query.single_mut();
+
+        if enemies_left_event.enemies_left != 0 {
+            continue;
+        }
+
+        if wave.boss_ahead(&config.boss) {
+            wave.boss_due = true;
+            wave.delay = Timer::from_seconds(config.waves.delay, TimerMode::Once);
+        } else if wave.number < config.waves.final_wave {
+            wave.pending = true;
+            wave.delay = Timer::from_seconds(config.waves.delay, TimerMode::Once);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_wave(
+    time: Res<Time>,
+    texture_atlas: Res<EnemyTextureAtlas>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    mut stats: ResMut<Stats>,
+    mut query: Query<&mut Wave>,
+    mut enemy_count_query: Query<&mut EnemyCount>,
+    mut enemies_left_events: EventWriter<EnemiesLeftEvent>,
+    mut wave_events: EventWriter<WaveEvent>,
+    mut commands: Commands,
+) {
+    let mut wave = query.single_mut();
+
+    if wave.pending && wave.delay.tick(time.delta()).finished() {
+        wave.pending = false;
+        wave.number += 1;
+        stats.wave = wave.number;
+
+        let count = wave.enemy_count(&config.waves);
+        add_enemies(
+            &mut commands,
+            &texture_atlas,
+            &mut rng,
+            &config.enemies,
+            wave.number,
+            count,
+            wave.speed_scale(&config.waves),
+        );
+
+        let mut enemy_count = enemy_count_query.single_mut();
+        enemy_count.count = count;
+
+        enemies_left_events.send(EnemiesLeftEvent {
+            enemies_left: count,
+        });
+        wave_events.send(WaveEvent { wave: wave.number });
+    } else if wave.boss_due && wave.delay.tick(time.delta()).finished() {
+        wave.boss_due = false;
+
+        add_boss(&mut commands, &texture_atlas, &config.boss);
+
+        let mut enemy_count = enemy_count_query.single_mut();
+        enemy_count.count += 1;
+
+        enemies_left_events.send(EnemiesLeftEvent {
+            enemies_left: enemy_count.count,
+        });
+    }
+}
+
+pub struct WavesPlugin;
+
+impl Plugin for WavesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_enter(AppState::Main).with_system(setup_wave))
+            .add_system_set(
+                SystemSet::on_update(AppState::Main)
+                    .with_system(handle_enemies_left.before(update_wave))
+                    .with_system(update_wave),
+            )
+            .add_system_set(SystemSet::on_exit(AppState::Main).with_system(despawn_with::<Wave>));
+    }
+}
@@ -0,0 +1,303 @@
+use crate::utils;
+use crate::{
+    bullet::add_bullet,
+    config::{BulletConfig, GameConfig},
+    controls::{Action, ActionInput},
+    events::{TransformEvent, WeaponEvent},
+    health::Damage,
+    state::{AppState, LoaderState},
+    stats::Stats,
+};
+use bevy::{input::mouse::MouseWheel, prelude::*, utils::Duration};
+
+static SPREAD_ANGLES: [f32; 3] = [-0.2, 0.0, 0.2];
+static SINGLE_ANGLE: [f32; 1] = [0.0];
+
+/// Weapon type of the character, decides how projectiles are fired and what they do.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WeaponKind {
+    Single,
+    Spread,
+    Rapid,
+    Laser,
+    Homing,
+}
+
+impl WeaponKind {
+    /// Every weapon, in the order of the weapon select actions.
+    pub const ALL: [WeaponKind; 5] = [
+        WeaponKind::Single,
+        WeaponKind::Spread,
+        WeaponKind::Rapid,
+        WeaponKind::Laser,
+        WeaponKind::Homing,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WeaponKind::Single => "Single",
+            WeaponKind::Spread => "Spread",
+            WeaponKind::Rapid => "Rapid",
+            WeaponKind::Laser => "Laser",
+            WeaponKind::Homing => "Homing",
+        }
+    }
+
+    fn index(&self) -> usize {
+        WeaponKind::ALL
+            .iter()
+            .position(|kind| kind == self)
+            .unwrap()
+    }
+
+    /// Neighbouring weapon, `step` of 1 is the next one and -1 the previous one.
+    pub fn cycle(&self, step: i32) -> Self {
+        let count = WeaponKind::ALL.len() as i32;
+        let index = (self.index() as i32 + step).rem_euclid(count);
+
+        WeaponKind::ALL[index as usize]
+    }
+
+    /// Next weapon in `ALL` order, the last one stays as it is.
+    pub fn upgrade(&self) -> Self {
+        WeaponKind::ALL
+            .get(self.index() + 1)
+            .copied()
+            .unwrap_or(*self)
+    }
+
+    /// Seconds between two shots, relative to `bullet.delay`.
+    pub fn cooldown(&self, config: &BulletConfig) -> f32 {
+        let factor = match self {
+            WeaponKind::Single => 1.0,
+            WeaponKind::Spread => 1.5,
+            WeaponKind::Rapid => 0.25,
+            WeaponKind::Laser => 2.0,
+            WeaponKind::Homing => 1.6,
+        };
+
+        config.delay * factor
+    }
+
+    /// Projectile speed, relative to `bullet.speed`.
+    pub fn speed(&self, config: &BulletConfig) -> f32 {
+        let factor = match self {
+            WeaponKind::Single => 1.0,
+            WeaponKind::Spread => 0.9,
+            WeaponKind::Rapid => 1.3,
+            WeaponKind::Laser => 3.0,
+            WeaponKind::Homing => 0.75,
+        };
+
+        config.speed * factor
+    }
+
+    /// Damage of a projectile, relative to `bullet.damage`.
+    pub fn damage(&self, config: &BulletConfig) -> Damage {
+        let factor = match self {
+            WeaponKind::Laser | WeaponKind::Homing => 2,
+            _ => 1,
+        };
+
+        Damage(config.damage * factor)
+    }
+
+    /// Keeps firing while the button is held instead of once per click.
+    pub fn is_automatic(&self) -> bool {
+        *self == WeaponKind::Rapid
+    }
+
+    /// Goes through enemies instead of stopping at the first one.
+    pub fn pierces(&self) -> bool {
+        *self == WeaponKind::Laser
+    }
+
+    pub fn homes(&self) -> bool {
+        *self == WeaponKind::Homing
+    }
+
+    /// Angles of the projectiles of one shot, around the aim.
+    pub fn angles(&self) -> &'static [f32] {
+        match self {
+            WeaponKind::Spread => &SPREAD_ANGLES,
+            _ => &SINGLE_ANGLE,
+        }
+    }
+
+    pub fn projectile_size(&self) -> utils::Size {
+        let (width, height) = match self {
+            WeaponKind::Single | WeaponKind::Spread => (5.0, 20.0),
+            WeaponKind::Rapid => (4.0, 12.0),
+            WeaponKind::Laser => (4.0, 48.0),
+            WeaponKind::Homing => (8.0, 16.0),
+        };
+
+        utils::Size { width, height }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            WeaponKind::Single | WeaponKind::Spread => Color::rgba(1.0, 0.0, 1.0, 0.8),
+            WeaponKind::Rapid => Color::rgba(1.0, 0.9, 0.2, 0.8),
+            WeaponKind::Laser => Color::rgba(0.3, 1.0, 1.0, 0.9),
+            WeaponKind::Homing => Color::rgba(1.0, 0.5, 0.0, 0.9),
+        }
+    }
+
+    pub fn sound(&self, loader: &LoaderState) -> Handle<AudioSource> {
+        match self {
+            WeaponKind::Laser => loader.collision_sound.clone(),
+            _ => loader.bullet_sound.clone(),
+        }
+    }
+
+    pub fn playback(&self) -> PlaybackSettings {
+        match self {
+            WeaponKind::Single => PlaybackSettings::ONCE,
+            WeaponKind::Spread => PlaybackSettings::ONCE.with_speed(0.8),
+            WeaponKind::Rapid => PlaybackSettings::ONCE.with_speed(1.4).with_volume(0.5),
+            WeaponKind::Laser => PlaybackSettings::ONCE.with_speed(1.8),
+            WeaponKind::Homing => PlaybackSettings::ONCE.with_speed(0.6),
+        }
+    }
+}
+
+/// Gun of the character, aimed by `TransformEvent`s.
+#[derive(Component)]
+pub struct Weapon {
+    pub kind: WeaponKind,
+    timer: Timer,
+    boost: Timer,
+    position: Vec2,
+    rotation: f32,
+    shots: u32,
+}
+
+impl Weapon {
+    pub fn new(kind: WeaponKind, initial_delay: f32) -> Self {
+        Self {
+            kind,
+            timer: Timer::from_seconds(initial_delay, TimerMode::Once),
+            boost: Timer::default(),
+            position: Vec2::new(0.0, 0.0),
+            rotation: 0.0,
+            shots: 0,
+        }
+    }
+
+    /// Scales the cooldown by `power_ups.boost_factor` for `seconds`.
+    pub fn boost(&mut self, seconds: f32) {
+        self.boost = Timer::from_seconds(seconds, TimerMode::Once);
+    }
+
+    pub fn is_boosted(&self) -> bool {
+        self.boost.elapsed() < self.boost.duration()
+    }
+}
+
+fn handle_transform(
+    mut transform_events: EventReader<TransformEvent>,
+    mut query: Query<&mut Weapon>,
+) {
+    for transform_event in transform_events.iter() {
+        for mut weapon in query.iter_mut() {
+            weapon.position = transform_event.position;
+            weapon.rotation = transform_event.rotation;
+        }
+    }
+}
+
+fn switch_weapon(
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    actions: ActionInput,
+    mut weapon_events: EventWriter<WeaponEvent>,
+    mut query: Query<&mut Weapon>,
+) {
+    let step: i32 = mouse_wheel_events
+        .iter()
+        .map(|event| event.y.signum() as i32)
+        .sum::<i32>()
+        + actions.just_pressed(Action::NextWeapon) as i32
+        - actions.just_pressed(Action::PreviousWeapon) as i32;
+    let selected = Action::WEAPONS
+        .iter()
+        .position(|action| actions.just_pressed(*action))
+        .map(|index| WeaponKind::ALL[index]);
+
+    for mut weapon in query.iter_mut() {
+        let kind = selected.unwrap_or_else(|| weapon.kind.cycle(step));
+
+        if kind != weapon.kind {
+            weapon.kind = kind;
+            weapon_events.send(WeaponEvent { weapon: kind });
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn fire_weapon(
+    time: Res<Time>,
+    actions: ActionInput,
+    loader: ResMut<LoaderState>,
+    audio: Res<Audio>,
+    config: Res<GameConfig>,
+    mut stats: ResMut<Stats>,
+    mut query: Query<&mut Weapon>,
+    mut commands: Commands,
+) {
+    for mut weapon in query.iter_mut() {
+        weapon.boost.tick(time.delta());
+
+        let kind = weapon.kind;
+        let trigger = if kind.is_automatic() {
+            actions.pressed(Action::Fire)
+        } else {
+            actions.just_pressed(Action::Fire)
+        };
+
+        if !weapon.timer.tick(time.delta()).finished() || !trigger {
+            continue;
+        }
+
+        let speed =
+            Vec2::new(-weapon.rotation.sin(), weapon.rotation.cos()) * kind.speed(&config.bullet);
+        weapon.shots += 1;
+        for angle in kind.angles() {
+            add_bullet(
+                &mut commands,
+                kind,
+                weapon.shots,
+                kind.damage(&config.bullet),
+                weapon.position,
+                Vec2::from_angle(*angle).rotate(speed),
+            );
+        }
+
+        let mut cooldown = kind.cooldown(&config.bullet);
+        if weapon.is_boosted() {
+            cooldown *= config.power_ups.boost_factor;
+        }
+        let cooldown = Duration::from_secs_f32(cooldown);
+        if weapon.timer.duration() != cooldown {
+            weapon.timer.set_duration(cooldown);
+        }
+
+        weapon.timer.reset();
+        stats.shots += kind.angles().len() as u32;
+
+        audio.play_with_settings(kind.sound(&loader), kind.playback());
+    }
+}
+
+pub struct WeaponPlugin;
+
+impl Plugin for WeaponPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(AppState::Main)
+                .with_system(handle_transform)
+                .with_system(switch_weapon.before(fire_weapon))
+                .with_system(fire_weapon),
+        );
+    }
+}
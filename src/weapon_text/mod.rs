@@ -0,0 +1,71 @@
+use crate::{
+    consts::{POSITION_Z, WINDOW_SIZE},
+    events::WeaponEvent,
+    state::{despawn_with, AppState, LoaderState},
+    weapon::WeaponKind,
+};
+use bevy::prelude::*;
+
+#[derive(Component)]
+struct WeaponText;
+
+fn add_weapon_text(mut commands: Commands, loader: Res<LoaderState>) {
+    let style = TextStyle {
+        font: loader.font.clone(),
+        font_size: 16.0,
+        color: Color::WHITE,
+    };
+
+    commands
+        .spawn(Text2dBundle {
+            text: Text {
+                sections: vec![
+                    TextSection {
+                        value: "Weapon:".to_string(),
+                        style: style.clone(),
+                    },
+                    TextSection {
+                        value: WeaponKind::Single.name().to_string(),
+                        style: TextStyle {
+                            color: Color::CYAN,
+                            ..style
+                        },
+                    },
+                ],
+                alignment: TextAlignment {
+                    horizontal: HorizontalAlign::Left,
+                    vertical: VerticalAlign::Top,
+                },
+            },
+            transform: Transform::from_xyz(
+                -WINDOW_SIZE.width / 2.0,
+                WINDOW_SIZE.height / 2.0 - 16.0 * 4.0,
+                POSITION_Z.weapon,
+            ),
+            ..default()
+        })
+        .insert(WeaponText);
+}
+
+fn update_weapon_text(
+    mut weapon_events: EventReader<WeaponEvent>,
+    mut query: Query<&mut Text, With<WeaponText>>,
+) {
+    for weapon_event in weapon_events.iter() {
+        for mut text in query.iter_mut() {
+            text.sections[1].value = weapon_event.weapon.name().to_string();
+        }
+    }
+}
+
+pub struct WeaponTextPlugin;
+
+impl Plugin for WeaponTextPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_enter(AppState::Main).with_system(add_weapon_text))
+            .add_system_set(SystemSet::on_update(AppState::Main).with_system(update_weapon_text))
+            .add_system_set(
+                SystemSet::on_exit(AppState::Main).with_system(despawn_with::<WeaponText>),
+            );
+    }
+}
@@ -0,0 +1,74 @@
+extern crate stars_rs;
+
+#[cfg(test)]
+mod config {
+    use bevy::{asset::LoadState, prelude::*};
+    use stars_rs::{
+        config::{ConfigError, GameConfig, CONFIG_PATH},
+        headless::HeadlessPlugin,
+        loader::LoaderPlugin,
+        StarsPluginGroup,
+    };
+
+    #[test]
+    fn test_asset_matches_defaults() {
+        let bytes = std::fs::read(format!("assets/{}", CONFIG_PATH)).unwrap();
+
+        assert_eq!(GameConfig::from_ron(&bytes).unwrap(), GameConfig::default());
+    }
+
+    #[test]
+    fn test_missing_fields_keep_defaults() {
+        let config = GameConfig::from_ron(b"(character: (lifes: 5), rain: ())").unwrap();
+
+        assert_eq!(config.character.lifes, 5);
+        assert_eq!(config.character.max_speed, 150.0);
+        assert_eq!(config.waves, GameConfig::default().waves);
+    }
+
+    #[test]
+    fn test_validation() {
+        let mut config = GameConfig::default();
+        config.enemies.min_speed = 300.0;
+
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError {
+                field: "enemies.max_speed",
+                message: "must not be below enemies.min_speed",
+            })
+        );
+
+        let error = GameConfig::from_ron(b"(character: (friction: 1.5))").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`character.friction` must be within (0, 1]"
+        );
+        assert!(GameConfig::from_ron(b"(character: (lifes: -1))").is_err());
+    }
+
+    #[test]
+    fn test_asset_loads() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(HeadlessPlugin)
+            .add_plugins(StarsPluginGroup.build().disable::<LoaderPlugin>());
+
+        let handle: Handle<GameConfig> = app.world.resource::<AssetServer>().load(CONFIG_PATH);
+        for _ in 0..100 {
+            app.update();
+            let load_state = app.world.resource::<AssetServer>().get_load_state(&handle);
+            if load_state != LoadState::Loading && load_state != LoadState::NotLoaded {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        app.update();
+
+        assert_eq!(
+            app.world.resource::<AssetServer>().get_load_state(&handle),
+            LoadState::Loaded
+        );
+        assert_eq!(app.world.resource::<GameConfig>(), &GameConfig::default());
+    }
+}
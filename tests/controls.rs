@@ -0,0 +1,38 @@
+extern crate stars_rs;
+
+#[cfg(test)]
+mod controls {
+    use bevy::prelude::*;
+    use stars_rs::controls::{Action, Binding, Keymap};
+
+    #[test]
+    fn test_bind_replaces_same_device() {
+        let mut keymap = Keymap::default();
+        keymap.bind(Action::Bomb, Binding::Key(KeyCode::B));
+
+        assert_eq!(
+            keymap.bindings(Action::Bomb),
+            &[
+                Binding::Gamepad(GamepadButtonType::LeftTrigger2),
+                Binding::Key(KeyCode::B),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bind_takes_binding_from_other_action() {
+        let mut keymap = Keymap::default();
+        keymap.bind(Action::Bomb, Binding::Key(KeyCode::W));
+
+        assert_eq!(
+            keymap.bindings(Action::MoveUp),
+            &[
+                Binding::Key(KeyCode::Up),
+                Binding::Gamepad(GamepadButtonType::DPadUp),
+            ]
+        );
+        assert!(keymap
+            .bindings(Action::Bomb)
+            .contains(&Binding::Key(KeyCode::W)));
+    }
+}
@@ -0,0 +1,36 @@
+extern crate stars_rs;
+
+#[cfg(test)]
+mod enemies {
+    use rand::{rngs::StdRng, SeedableRng};
+    use stars_rs::enemies::EnemyKind;
+
+    #[test]
+    fn test_first_wave_is_drifters() {
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for _ in 0..50 {
+            assert_eq!(EnemyKind::random(&mut rng, 1), EnemyKind::Drifter);
+        }
+    }
+
+    #[test]
+    fn test_waves_unlock_kinds() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let kinds: Vec<EnemyKind> = (0..200).map(|_| EnemyKind::random(&mut rng, 5)).collect();
+
+        for kind in [
+            EnemyKind::Drifter,
+            EnemyKind::Weaver,
+            EnemyKind::Chaser,
+            EnemyKind::Splitter,
+            EnemyKind::Tank,
+        ] {
+            assert!(kinds.contains(&kind));
+        }
+        assert!(!kinds.contains(&EnemyKind::Shard));
+        assert!(!(0..200)
+            .map(|_| EnemyKind::random(&mut rng, 2))
+            .any(|kind| kind == EnemyKind::Chaser));
+    }
+}
@@ -0,0 +1,1605 @@
+extern crate stars_rs;
+
+#[cfg(test)]
+mod headless {
+    use bevy::{
+        ecs::system::CommandQueue,
+        input::{
+            gamepad::{GamepadEventRaw, GamepadEventType, GamepadInfo},
+            keyboard::KeyboardInput,
+            mouse::{MouseButtonInput, MouseScrollUnit, MouseWheel},
+            touch::{TouchInput, TouchPhase},
+            ButtonState,
+        },
+        prelude::*,
+        window::WindowId,
+    };
+    use stars_rs::{
+        aim::Aim,
+        bomb::BombCharges,
+        boss::{add_boss, Boss, BossPhase},
+        bullet::Bullet,
+        character::{Character, CharacterActive, CharacterLifes},
+        config::GameConfig,
+        consts::WINDOW_SIZE,
+        controls::{Action, Binding, BindingSlot, ControlsButton, Device, Keymap},
+        enemies::{Enemy, EnemyCount, EnemyKind, EnemyTextureAtlas, ENEMY_SIZE},
+        enemy_bullet::{EnemyBullet, EnemyGun},
+        events::{AddExplosionEvent, CollisionEvent, CollisionKind},
+        headless::HeadlessPlugin,
+        health::{Health, HitFlash},
+        high_scores::{HighScores, NameEntry},
+        loader::LoaderPlugin,
+        pause::PauseButton,
+        power_ups::{PowerUp, PowerUpKind},
+        replay::{Recorder, Replay, ReplayError, ReplayPlugin},
+        results::PlayAgainButton,
+        rng::RngPlugin,
+        score::Score,
+        state::AppState,
+        stats::Stats,
+        storage::Storage,
+        timestep::{PreviousPosition, TIMESTEP},
+        touch::{
+            TouchControls, AIM_STICK_CENTER, FIRE_BUTTON_CENTER, MOVE_STICK_CENTER, STICK_RADIUS,
+        },
+        utils::{GetBoundingRect, IsActive},
+        waves::Wave,
+        weapon::{Weapon, WeaponKind},
+        StarsPluginGroup,
+    };
+    use std::f32::consts::PI;
+
+    /// Updates a new app runs before it's handed to a test: the one leaving `Loading`,
+    /// which starts the run, and the next one.
+    static STARTUP_UPDATES: usize = 2;
+
+    fn headless_app() -> App {
+        seeded_app(None)
+    }
+
+    fn seeded_app(seed: Option<u64>) -> App {
+        replay_app(seed, None)
+    }
+
+    fn replay_app(seed: Option<u64>, replay: Option<Replay>) -> App {
+        let mut app = unstarted_app(seed, replay);
+        for _ in 0..STARTUP_UPDATES {
+            app.update();
+        }
+
+        app
+    }
+
+    /// App that hasn't run its startup systems yet.
+    fn unstarted_app(seed: Option<u64>, replay: Option<Replay>) -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(HeadlessPlugin)
+            .add_plugins(
+                StarsPluginGroup
+                    .build()
+                    .disable::<LoaderPlugin>()
+                    .set(RngPlugin { seed })
+                    .set(ReplayPlugin { replay }),
+            );
+
+        app
+    }
+
+    /// App loading player data from `storage`.
+    fn storage_app(storage: Storage) -> App {
+        let mut app = unstarted_app(None, None);
+        app.insert_resource(storage);
+        for _ in 0..STARTUP_UPDATES {
+            app.update();
+        }
+
+        app
+    }
+
+    fn enemies(app: &mut App) -> usize {
+        app.world.query::<&Enemy>().iter(&app.world).count()
+    }
+
+    fn enemy_count(app: &mut App) -> u32 {
+        app.world.query::<&EnemyCount>().single(&app.world).count
+    }
+
+    fn tap_key(app: &mut App, key_code: KeyCode) {
+        for state in [ButtonState::Pressed, ButtonState::Released] {
+            app.world.send_event(KeyboardInput {
+                scan_code: 0,
+                key_code: Some(key_code),
+                state,
+            });
+            app.update();
+        }
+    }
+
+    fn line_up_enemies(app: &mut App) -> Vec<Vec2> {
+        app.world
+            .query::<&mut Enemy>()
+            .iter_mut(&mut app.world)
+            .enumerate()
+            .map(|(index, mut enemy)| {
+                enemy.position = Vec2::new(index as f32 * 100.0 - 200.0, 200.0);
+                enemy.speed = Vec2::ZERO;
+                enemy.position
+            })
+            .collect()
+    }
+
+    fn character_lifes(app: &mut App) -> u32 {
+        app.world
+            .query::<&CharacterLifes>()
+            .single(&app.world)
+            .lifes
+    }
+
+    #[test]
+    fn test_skips_loading() {
+        let mut app = headless_app();
+
+        assert_eq!(
+            app.world.resource::<State<AppState>>().current(),
+            &AppState::Main
+        );
+        assert_eq!(enemies(&mut app), 5);
+        assert_eq!(enemy_count(&mut app), 5);
+        assert_eq!(character_lifes(&mut app), 3);
+    }
+
+    #[test]
+    fn test_seeded_spawns() {
+        let mut lhs = seeded_app(Some(7));
+        let mut rhs = seeded_app(Some(7));
+
+        let spawns = |app: &mut App| -> Vec<Vec2> {
+            app.world
+                .query::<&Enemy>()
+                .iter(&app.world)
+                .map(|enemy| enemy.speed)
+                .collect()
+        };
+
+        assert_eq!(spawns(&mut lhs), spawns(&mut rhs));
+    }
+
+    #[test]
+    fn test_fixed_timestep() {
+        let mut app = headless_app();
+
+        let (entity, speed) = {
+            let (entity, mut enemy) = app
+                .world
+                .query::<(Entity, &mut Enemy)>()
+                .iter_mut(&mut app.world)
+                .next()
+                .unwrap();
+            enemy.position = Vec2::new(100.0, 100.0);
+            (entity, enemy.speed)
+        };
+        app.update();
+        app.update();
+
+        let position = app.world.get::<Enemy>(entity).unwrap().position;
+        let expected = Vec2::new(100.0, 100.0) + speed * 2.0 * TIMESTEP as f32;
+        assert!(position.abs_diff_eq(expected, 0.001));
+    }
+
+    #[test]
+    fn test_character_collision() {
+        let mut app = headless_app();
+
+        let position = app.world.query::<&Character>().single(&app.world).position;
+        for mut enemy in app.world.query::<&mut Enemy>().iter_mut(&mut app.world) {
+            enemy.position = position;
+        }
+        app.update();
+
+        assert_eq!(character_lifes(&mut app), 2);
+        let character_active = app.world.query::<&CharacterActive>().single(&app.world);
+        assert!(!character_active.get_active());
+    }
+
+    #[test]
+    fn test_bullet_collision() {
+        let mut app = headless_app();
+
+        let positions = line_up_enemies(&mut app);
+        shoot(&mut app, positions[0]);
+        app.update();
+        app.update();
+
+        assert_eq!(enemies(&mut app), 4);
+        assert_eq!(enemy_count(&mut app), 4);
+        assert_eq!(app.world.query::<&Bullet>().iter(&app.world).count(), 0);
+    }
+
+    #[test]
+    fn test_fast_bullet_collision() {
+        let mut app = headless_app();
+
+        let positions = line_up_enemies(&mut app);
+        // Far enough in a tick to skip over the enemy.
+        let start = positions[0] - Vec2::new(0.0, 100.0);
+        let speed = Vec2::new(0.0, 200.0 / TIMESTEP as f32);
+        let damage = WeaponKind::Single.damage(&app.world.resource::<GameConfig>().bullet);
+        app.world
+            .spawn((Bullet::new(start, speed), PreviousPosition(start), damage));
+        app.update();
+
+        assert_eq!(enemies(&mut app), 4);
+        assert!(!app
+            .world
+            .query::<&Enemy>()
+            .iter(&app.world)
+            .any(|enemy| enemy.position == positions[0]));
+        assert_eq!(app.world.resource::<Stats>().hits, 1);
+    }
+
+    /// Spawns the boss at `position` right away.
+    fn spawn_boss(app: &mut App, position: Vec2) {
+        let mut command_queue = CommandQueue::default();
+        add_boss(
+            &mut Commands::new(&mut command_queue, &app.world),
+            app.world.resource::<EnemyTextureAtlas>(),
+            &app.world.resource::<GameConfig>().boss,
+        );
+        command_queue.apply(&mut app.world);
+
+        move_boss(app, position);
+    }
+
+    fn move_boss(app: &mut App, position: Vec2) {
+        let (mut boss, mut previous_position) = app
+            .world
+            .query::<(&mut Boss, &mut PreviousPosition)>()
+            .single_mut(&mut app.world);
+        boss.position = position;
+        previous_position.0 = position;
+    }
+
+    fn boss_health(app: &mut App) -> Health {
+        *app.world
+            .query_filtered::<&Health, With<Boss>>()
+            .single(&app.world)
+    }
+
+    #[test]
+    fn test_bullet_hits_nearest_target() {
+        let mut app = headless_app();
+
+        // The boss right behind the first enemy, a bullet crossing both in one tick.
+        let positions = line_up_enemies(&mut app);
+        spawn_boss(&mut app, positions[0] + Vec2::new(0.0, 150.0));
+        let health = boss_health(&mut app);
+        let damage = WeaponKind::Single.damage(&app.world.resource::<GameConfig>().bullet);
+        let start = positions[0] - Vec2::new(0.0, 100.0);
+        let speed = Vec2::new(0.0, 350.0 / TIMESTEP as f32);
+        app.world
+            .spawn((Bullet::new(start, speed), PreviousPosition(start), damage));
+        app.update();
+
+        assert_eq!(enemies(&mut app), 4);
+        assert_eq!(boss_health(&mut app), health);
+
+        // The boss in front of the next enemy takes the bullet instead.
+        move_boss(&mut app, positions[1] - Vec2::new(0.0, 150.0));
+        let start = positions[1] - Vec2::new(0.0, 350.0);
+        app.world
+            .spawn((Bullet::new(start, speed), PreviousPosition(start), damage));
+        app.update();
+
+        assert_eq!(enemies(&mut app), 4);
+        assert!(boss_health(&mut app).current < health.current);
+        assert_eq!(app.world.resource::<Stats>().hits, 2);
+    }
+
+    fn collision_events(app: &App) -> Vec<CollisionEvent> {
+        let events = app.world.resource::<Events<CollisionEvent>>();
+        events.get_reader().iter(events).copied().collect()
+    }
+
+    #[test]
+    fn test_collision_events() {
+        let mut app = headless_app();
+
+        let positions = line_up_enemies(&mut app);
+        let (character_entity, mut character) = app
+            .world
+            .query::<(Entity, &mut Character)>()
+            .single_mut(&mut app.world);
+        character.position = positions[4];
+        let (shot_enemy, rammed_enemy) = {
+            let mut enemy_at = |position| {
+                app.world
+                    .query::<(Entity, &Enemy)>()
+                    .iter(&app.world)
+                    .find(|(_, enemy)| enemy.position == position)
+                    .unwrap()
+                    .0
+            };
+            (enemy_at(positions[0]), enemy_at(positions[4]))
+        };
+        shoot(&mut app, positions[0]);
+        app.update();
+
+        let events = collision_events(&app);
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().any(|event| event.b == shot_enemy
+            && event.kind == CollisionKind::Shot
+            && app.world.get::<Bullet>(event.a).is_none()));
+        assert!(events.contains(&CollisionEvent {
+            a: rammed_enemy,
+            b: character_entity,
+            kind: CollisionKind::Ram,
+        }));
+        assert_eq!(enemies(&mut app), 4);
+        assert_eq!(character_lifes(&mut app), 2);
+    }
+
+    /// Spawns a standing single shot bullet at `position`.
+    fn shoot(app: &mut App, position: Vec2) {
+        let damage = WeaponKind::Single.damage(&app.world.resource::<GameConfig>().bullet);
+        app.world.spawn((Bullet::new(position, Vec2::ZERO), damage));
+    }
+
+    fn make_first_enemy(app: &mut App, kind: EnemyKind) -> Entity {
+        let health = Health::new(kind.health(&app.world.resource::<GameConfig>().enemies));
+        let (entity, mut enemy_health) = app
+            .world
+            .query_filtered::<(Entity, &mut Health), With<Enemy>>()
+            .iter_mut(&mut app.world)
+            .next()
+            .unwrap();
+        *enemy_health = health;
+
+        app.world.entity_mut(entity).insert(kind);
+        entity
+    }
+
+    #[test]
+    fn test_tank_health() {
+        let mut app = headless_app();
+
+        let positions = line_up_enemies(&mut app);
+        let entity = make_first_enemy(&mut app, EnemyKind::Tank);
+        let position = app.world.get::<Enemy>(entity).unwrap().position;
+        assert!(positions.contains(&position));
+
+        for (combo, health) in [(1, 20), (2, 10)] {
+            shoot(&mut app, position);
+            app.update();
+            app.update();
+
+            assert_eq!(app.world.get::<Health>(entity).unwrap().current, health);
+            assert_eq!(enemy_count(&mut app), 5);
+            assert_eq!(app.world.resource::<Score>().combo, combo);
+        }
+
+        shoot(&mut app, position);
+        app.update();
+        app.update();
+
+        assert!(app.world.get::<Enemy>(entity).is_none());
+        assert_eq!(enemy_count(&mut app), 4);
+        assert_eq!(
+            app.world.resource::<Score>().points,
+            EnemyKind::Tank.score() * 3
+        );
+    }
+
+    #[test]
+    fn test_damage_feedback() {
+        let mut app = headless_app();
+
+        line_up_enemies(&mut app);
+        let entity = make_first_enemy(&mut app, EnemyKind::Tank);
+        let position = app.world.get::<Enemy>(entity).unwrap().position;
+        shoot(&mut app, position);
+        app.update();
+        app.update();
+
+        assert!(app.world.get::<HitFlash>(entity).unwrap().is_flashing());
+        assert_eq!(app.world.resource::<Score>().points, 0);
+        let damage_numbers = app
+            .world
+            .query::<&Text>()
+            .iter(&app.world)
+            .filter(|text| text.sections[0].value == "10")
+            .count();
+        assert_eq!(damage_numbers, 1);
+
+        for _ in 0..(1.0 / TIMESTEP) as u32 {
+            app.update();
+        }
+
+        assert!(!app.world.get::<HitFlash>(entity).unwrap().is_flashing());
+        let damage_numbers = app
+            .world
+            .query::<&Text>()
+            .iter(&app.world)
+            .filter(|text| text.sections[0].value == "10")
+            .count();
+        assert_eq!(damage_numbers, 0);
+    }
+
+    #[test]
+    fn test_splitter_shards() {
+        let mut app = headless_app();
+
+        line_up_enemies(&mut app);
+        let entity = make_first_enemy(&mut app, EnemyKind::Splitter);
+        let position = app.world.get::<Enemy>(entity).unwrap().position;
+        shoot(&mut app, position);
+        app.update();
+        app.update();
+
+        let shards = app
+            .world
+            .query::<&EnemyKind>()
+            .iter(&app.world)
+            .filter(|kind| **kind == EnemyKind::Shard)
+            .count();
+        assert_eq!(shards, 2);
+        assert_eq!(enemies(&mut app), 6);
+        assert_eq!(enemy_count(&mut app), 6);
+    }
+
+    #[test]
+    fn test_chaser_steers() {
+        let mut app = headless_app();
+
+        let entity = make_first_enemy(&mut app, EnemyKind::Chaser);
+        let character_position = app.world.query::<&Character>().single(&app.world).position;
+        {
+            let mut enemy = app.world.get_mut::<Enemy>(entity).unwrap();
+            enemy.position = character_position + Vec2::new(300.0, 0.0);
+            enemy.speed = Vec2::new(0.0, 100.0);
+        }
+        for _ in 0..30 {
+            app.update();
+        }
+
+        let enemy = app.world.get::<Enemy>(entity).unwrap();
+        assert!(enemy.speed.x < 0.0);
+        assert!((enemy.speed.length() - 100.0).abs() < 1.0);
+    }
+
+    fn enemy_bullets(app: &mut App) -> usize {
+        app.world.query::<&EnemyBullet>().iter(&app.world).count()
+    }
+
+    #[test]
+    fn test_shooter_fires() {
+        let mut app = headless_app();
+
+        let entity = make_first_enemy(&mut app, EnemyKind::Shooter);
+        app.world.entity_mut(entity).insert(EnemyGun::default());
+        {
+            let mut enemy = app.world.get_mut::<Enemy>(entity).unwrap();
+            enemy.position = Vec2::new(150.0, 150.0);
+            enemy.speed = Vec2::ZERO;
+        }
+        for mut enemy in app.world.query::<&mut Enemy>().iter_mut(&mut app.world) {
+            enemy.speed = Vec2::ZERO;
+        }
+
+        let cooldown_ticks = (2.0 / TIMESTEP) as u32 + 2;
+        for _ in 0..cooldown_ticks {
+            app.update();
+        }
+        assert_eq!(enemy_bullets(&mut app), 1);
+
+        let spawned: Vec<Entity> = app
+            .world
+            .query_filtered::<Entity, With<EnemyBullet>>()
+            .iter(&app.world)
+            .collect();
+        for entity in spawned {
+            app.world.despawn(entity);
+        }
+        for _ in 0..cooldown_ticks {
+            app.update();
+        }
+        assert_eq!(enemy_bullets(&mut app), 3);
+    }
+
+    #[test]
+    fn test_enemy_bullet_collision() {
+        let mut app = headless_app();
+
+        let position = app.world.query::<&Character>().single(&app.world).position;
+        app.world
+            .spawn(EnemyBullet::new(position, Vec2::ZERO))
+            .insert(PreviousPosition(position));
+        app.update();
+
+        assert_eq!(character_lifes(&mut app), 2);
+        let character_active = app.world.query::<&CharacterActive>().single(&app.world);
+        assert!(!character_active.get_active());
+        assert_eq!(enemy_bullets(&mut app), 0);
+    }
+
+    #[test]
+    fn test_enemy_bullets_while_inactive() {
+        let mut app = headless_app();
+
+        // The first bullet takes a life, the second reaches an invulnerable character.
+        let position = app.world.query::<&Character>().single(&app.world).position;
+        for _ in 0..2 {
+            app.world
+                .spawn(EnemyBullet::new(position, Vec2::ZERO))
+                .insert(PreviousPosition(position));
+        }
+        app.update();
+
+        assert_eq!(character_lifes(&mut app), 2);
+        assert_eq!(enemy_bullets(&mut app), 1);
+    }
+
+    #[test]
+    fn test_score() {
+        let mut app = headless_app();
+
+        let positions = line_up_enemies(&mut app);
+        for position in &positions[..2] {
+            shoot(&mut app, *position);
+            app.update();
+            app.update();
+        }
+
+        let score = app.world.resource::<Score>();
+        assert_eq!(score.points, 300);
+        assert_eq!(score.multiplier(), 2);
+
+        // Both bullets of a spread missing only count once.
+        let position = Vec2::new(0.0, 10000.0);
+        for _ in 0..2 {
+            app.world
+                .spawn(Bullet::fired(WeaponKind::Spread, 1, position, Vec2::ZERO))
+                .insert(PreviousPosition(position));
+        }
+        app.update();
+
+        let score = app.world.resource::<Score>();
+        assert_eq!(score.points, 300);
+        assert_eq!(score.combo, 1);
+    }
+
+    #[test]
+    fn test_next_wave() {
+        let mut app = headless_app();
+
+        for position in line_up_enemies(&mut app) {
+            shoot(&mut app, position);
+        }
+        app.update();
+        app.update();
+
+        assert_eq!(enemies(&mut app), 0);
+        assert_eq!(app.world.query::<&Wave>().single(&app.world).number, 1);
+
+        for _ in 0..(3.0 / TIMESTEP) as u32 {
+            app.update();
+        }
+
+        assert_eq!(app.world.query::<&Wave>().single(&app.world).number, 2);
+        assert_eq!(enemies(&mut app), 7);
+        assert_eq!(enemy_count(&mut app), 7);
+    }
+
+    fn weapon(app: &mut App) -> WeaponKind {
+        app.world.query::<&Weapon>().single(&app.world).kind
+    }
+
+    fn scroll(app: &mut App, y: f32) {
+        app.world.send_event(MouseWheel {
+            unit: MouseScrollUnit::Line,
+            x: 0.0,
+            y,
+        });
+        app.update();
+    }
+
+    /// Holds the left button for `seconds`, returns how many projectiles were fired.
+    fn hold_fire(app: &mut App, seconds: f64) -> u32 {
+        hold(app, Binding::Mouse(MouseButton::Left), seconds)
+    }
+
+    /// Holds a key or mouse button for `seconds`, returns how many projectiles were
+    /// fired.
+    fn hold(app: &mut App, binding: Binding, seconds: f64) -> u32 {
+        let shots = app.world.resource::<Stats>().shots;
+
+        let send = |app: &mut App, state| match binding {
+            Binding::Key(key_code) => app.world.send_event(KeyboardInput {
+                scan_code: 0,
+                key_code: Some(key_code),
+                state,
+            }),
+            Binding::Mouse(button) => app.world.send_event(MouseButtonInput { button, state }),
+            Binding::Gamepad(_) => unimplemented!(),
+        };
+        send(app, ButtonState::Pressed);
+        for _ in 0..(seconds / TIMESTEP) as u32 {
+            app.update();
+        }
+        send(app, ButtonState::Released);
+        for _ in 0..(1.0 / TIMESTEP) as u32 {
+            app.update();
+        }
+
+        app.world.resource::<Stats>().shots - shots
+    }
+
+    #[test]
+    fn test_weapon_switch() {
+        let mut app = headless_app();
+        assert_eq!(weapon(&mut app), WeaponKind::Single);
+
+        tap_key(&mut app, KeyCode::Key3);
+        assert_eq!(weapon(&mut app), WeaponKind::Rapid);
+
+        scroll(&mut app, 1.0);
+        assert_eq!(weapon(&mut app), WeaponKind::Laser);
+
+        scroll(&mut app, -1.0);
+        scroll(&mut app, -1.0);
+        assert_eq!(weapon(&mut app), WeaponKind::Spread);
+
+        tap_key(&mut app, KeyCode::Key1);
+        scroll(&mut app, -1.0);
+        assert_eq!(weapon(&mut app), WeaponKind::Homing);
+    }
+
+    #[test]
+    fn test_weapon_fire() {
+        let mut app = headless_app();
+        for _ in 0..10 {
+            app.update();
+        }
+
+        assert_eq!(hold_fire(&mut app, 1.0), 1);
+
+        tap_key(&mut app, KeyCode::Key2);
+        assert_eq!(hold_fire(&mut app, 1.0), 3);
+
+        tap_key(&mut app, KeyCode::Key3);
+        assert!(hold_fire(&mut app, 1.0) >= 6);
+    }
+
+    #[test]
+    fn test_laser_pierces() {
+        let mut app = headless_app();
+
+        let positions = line_up_enemies(&mut app);
+        let start = Vec2::new(positions[0].x - 100.0, positions[0].y);
+        app.world
+            .spawn(Bullet::fired(
+                WeaponKind::Laser,
+                1,
+                start,
+                Vec2::new(900.0, 0.0),
+            ))
+            .insert(PreviousPosition(start))
+            .insert(WeaponKind::Laser.damage(&GameConfig::default().bullet));
+        for _ in 0..(0.6 / TIMESTEP) as u32 {
+            app.update();
+        }
+
+        assert_eq!(enemies(&mut app), 0);
+        assert_eq!(enemy_count(&mut app), 0);
+        assert_eq!(app.world.resource::<Stats>().hits, 1);
+    }
+
+    #[test]
+    fn test_homing_turns() {
+        let mut app = headless_app();
+
+        line_up_enemies(&mut app);
+        let start = Vec2::new(0.0, -200.0);
+        app.world
+            .spawn(Bullet::fired(
+                WeaponKind::Homing,
+                1,
+                start,
+                Vec2::new(200.0, 0.0),
+            ))
+            .insert(PreviousPosition(start))
+            .insert(WeaponKind::Homing.damage(&GameConfig::default().bullet));
+        for _ in 0..30 {
+            app.update();
+        }
+
+        let bullet = app.world.query::<&Bullet>().single(&app.world);
+        assert!(bullet.speed().y > 0.0);
+        assert!((bullet.speed().length() - 200.0).abs() < 0.01);
+    }
+
+    fn power_ups(app: &mut App) -> Vec<Vec2> {
+        app.world
+            .query::<&PowerUp>()
+            .iter(&app.world)
+            .map(|power_up| power_up.position)
+            .collect()
+    }
+
+    fn add_power_up(app: &mut App, kind: PowerUpKind, position: Vec2, lifetime: f32) {
+        app.world
+            .spawn(PowerUp::new(kind, position, Vec2::ZERO, lifetime))
+            .insert(PreviousPosition(position));
+    }
+
+    fn character_position(app: &mut App) -> Vec2 {
+        app.world.query::<&Character>().single(&app.world).position
+    }
+
+    #[test]
+    fn test_power_up_drop() {
+        let mut app = headless_app();
+        app.world.resource_mut::<GameConfig>().power_ups.drop_chance = 1.0;
+
+        let positions = line_up_enemies(&mut app);
+        shoot(&mut app, positions[0]);
+        app.update();
+        app.update();
+
+        let dropped = power_ups(&mut app);
+        assert_eq!(dropped.len(), 1);
+        assert!(dropped[0].distance(positions[0]) < 5.0);
+    }
+
+    #[test]
+    fn test_power_up_expires() {
+        let mut app = headless_app();
+
+        add_power_up(&mut app, PowerUpKind::Shield, Vec2::new(250.0, -200.0), 1.0);
+        for _ in 0..(0.9 / TIMESTEP) as u32 {
+            app.update();
+        }
+        assert_eq!(power_ups(&mut app).len(), 1);
+
+        for _ in 0..(0.2 / TIMESTEP) as u32 {
+            app.update();
+        }
+        assert_eq!(power_ups(&mut app).len(), 0);
+    }
+
+    #[test]
+    fn test_power_up_effects() {
+        let mut app = headless_app();
+        let position = character_position(&mut app);
+
+        add_power_up(&mut app, PowerUpKind::ExtraLife, position, 8.0);
+        app.update();
+        assert!(collision_events(&app)
+            .iter()
+            .any(|event| event.kind == CollisionKind::Pickup));
+        assert_eq!(power_ups(&mut app).len(), 0);
+        assert_eq!(character_lifes(&mut app), 4);
+
+        add_power_up(&mut app, PowerUpKind::WeaponUpgrade, position, 8.0);
+        add_power_up(&mut app, PowerUpKind::RapidFire, position, 8.0);
+        app.update();
+        let weapon = app.world.query::<&Weapon>().single(&app.world);
+        assert_eq!(weapon.kind, WeaponKind::Spread);
+        assert!(weapon.is_boosted());
+
+        add_power_up(&mut app, PowerUpKind::Shield, position, 8.0);
+        app.update();
+        for _ in 0..(4.0 / TIMESTEP) as u32 {
+            app.update();
+        }
+        let character_active = app.world.query::<&CharacterActive>().single(&app.world);
+        assert!(!character_active.get_active());
+    }
+
+    #[test]
+    fn test_bomb_power_up() {
+        let mut app = headless_app();
+        let position = character_position(&mut app);
+
+        app.world
+            .spawn(EnemyBullet::new(Vec2::new(200.0, -200.0), Vec2::ZERO))
+            .insert(PreviousPosition(Vec2::new(200.0, -200.0)));
+        add_power_up(&mut app, PowerUpKind::Bomb, position, 8.0);
+        app.update();
+        app.update();
+
+        assert_eq!(enemies(&mut app), 0);
+        assert_eq!(enemy_count(&mut app), 0);
+        assert_eq!(enemy_bullets(&mut app), 0);
+        assert_eq!(character_lifes(&mut app), 3);
+    }
+
+    fn bomb_charges(app: &mut App) -> u32 {
+        app.world.query::<&BombCharges>().single(&app.world).charges
+    }
+
+    #[test]
+    fn test_bomb() {
+        let mut app = headless_app();
+        assert_eq!(bomb_charges(&mut app), 3);
+
+        line_up_enemies(&mut app);
+        for position in [Vec2::new(0.0, -100.0), Vec2::new(280.0, -230.0)] {
+            app.world
+                .spawn(EnemyBullet::new(position, Vec2::ZERO))
+                .insert(PreviousPosition(position));
+        }
+        tap_key(&mut app, KeyCode::Space);
+
+        assert_eq!(bomb_charges(&mut app), 2);
+        assert_eq!(enemies(&mut app), 2);
+        assert_eq!(enemy_count(&mut app), 2);
+        assert!(app.world.resource::<Score>().points > 0);
+        assert_eq!(enemy_bullets(&mut app), 1);
+        assert_eq!(character_lifes(&mut app), 3);
+        let character_active = app.world.query::<&CharacterActive>().single(&app.world);
+        assert!(!character_active.get_active());
+
+        for _ in 0..3 {
+            tap_key(&mut app, KeyCode::Space);
+        }
+        assert_eq!(bomb_charges(&mut app), 0);
+    }
+
+    #[test]
+    fn test_rebound_bomb() {
+        let mut app = headless_app();
+        app.world
+            .resource_mut::<Keymap>()
+            .bind(Action::Bomb, Binding::Key(KeyCode::B));
+
+        tap_key(&mut app, KeyCode::Space);
+        assert_eq!(bomb_charges(&mut app), 3);
+
+        tap_key(&mut app, KeyCode::B);
+        assert_eq!(bomb_charges(&mut app), 2);
+    }
+
+    fn open_controls(app: &mut App) {
+        let mut state = app.world.resource_mut::<State<AppState>>();
+        state.replace(AppState::Menu).unwrap();
+        app.update();
+        let mut state = app.world.resource_mut::<State<AppState>>();
+        state.set(AppState::Controls).unwrap();
+        app.update();
+    }
+
+    /// Clicks the keyboard and mouse slot of `action` on the controls screen.
+    fn click_slot(app: &mut App, action: Action) {
+        for (mut interaction, slot) in app
+            .world
+            .query::<(&mut Interaction, &BindingSlot)>()
+            .iter_mut(&mut app.world)
+        {
+            if slot.action == action && slot.device == Device::KeyboardMouse {
+                *interaction = Interaction::Clicked;
+            }
+        }
+        app.update();
+
+        for mut interaction in app
+            .world
+            .query::<&mut Interaction>()
+            .iter_mut(&mut app.world)
+        {
+            *interaction = Interaction::None;
+        }
+    }
+
+    #[test]
+    fn test_rebind_fire() {
+        let mut app = headless_app();
+        open_controls(&mut app);
+
+        click_slot(&mut app, Action::Fire);
+        tap_key(&mut app, KeyCode::F);
+        let keymap = app.world.resource::<Keymap>().clone();
+        assert_eq!(
+            keymap.bindings(Action::Fire),
+            [
+                Binding::Gamepad(GamepadButtonType::RightTrigger2),
+                Binding::Key(KeyCode::F)
+            ]
+        );
+
+        tap_key(&mut app, KeyCode::Escape);
+        let mut state = app.world.resource_mut::<State<AppState>>();
+        state.set(AppState::Main).unwrap();
+        for _ in 0..10 {
+            app.update();
+        }
+
+        assert_eq!(hold_fire(&mut app, 1.0), 0);
+        assert_eq!(hold(&mut app, Binding::Key(KeyCode::F), 1.0), 1);
+
+        // A new app loading from the same storage gets the new binding back.
+        let storage = app.world.resource::<Storage>().clone();
+        let app = storage_app(storage);
+        assert_eq!(*app.world.resource::<Keymap>(), keymap);
+    }
+
+    #[test]
+    fn test_rebind_ignores_buttons() {
+        let mut app = headless_app();
+        open_controls(&mut app);
+
+        click_slot(&mut app, Action::Fire);
+        app.world.send_event(MouseButtonInput {
+            button: MouseButton::Left,
+            state: ButtonState::Pressed,
+        });
+        for (mut interaction, controls_button) in app
+            .world
+            .query::<(&mut Interaction, &ControlsButton)>()
+            .iter_mut(&mut app.world)
+        {
+            if *controls_button == ControlsButton::Back {
+                *interaction = Interaction::Clicked;
+            }
+        }
+        app.update();
+        app.update();
+
+        assert_eq!(
+            app.world.resource::<State<AppState>>().current(),
+            &AppState::Menu
+        );
+        assert_eq!(*app.world.resource::<Keymap>(), Keymap::default());
+
+        let storage = app.world.resource::<Storage>().clone();
+        let app = storage_app(storage);
+        assert_eq!(*app.world.resource::<Keymap>(), Keymap::default());
+    }
+
+    /// Makes the first wave the boss wave, clears it and waits for the boss.
+    fn summon_boss(app: &mut App) {
+        app.world.resource_mut::<GameConfig>().boss.wave = 1;
+
+        for position in line_up_enemies(app) {
+            shoot(app, position);
+        }
+        for _ in 0..(2.5 / TIMESTEP) as u32 {
+            app.update();
+            if boss(app).is_some() {
+                break;
+            }
+        }
+    }
+
+    fn boss(app: &mut App) -> Option<&Boss> {
+        app.world.query::<&Boss>().iter(&app.world).next()
+    }
+
+    #[test]
+    fn test_boss_appears() {
+        let mut app = headless_app();
+        app.world.resource_mut::<GameConfig>().boss.phase_duration = 1.0;
+
+        summon_boss(&mut app);
+
+        let rect = boss(&mut app).unwrap().get_bounding_rect();
+        assert!(rect.width > ENEMY_SIZE.width);
+        assert_eq!(boss(&mut app).unwrap().phase(), BossPhase::Rings);
+        assert_eq!(enemies(&mut app), 0);
+        assert_eq!(enemy_count(&mut app), 1);
+        assert_eq!(app.world.query::<&Wave>().single(&app.world).number, 1);
+
+        for _ in 0..(1.5 / TIMESTEP) as u32 {
+            app.update();
+        }
+        assert!(enemy_bullets(&mut app) > 0);
+        assert_eq!(boss(&mut app).unwrap().phase(), BossPhase::Charge);
+
+        for _ in 0..(1.0 / TIMESTEP) as u32 {
+            app.update();
+        }
+        assert_eq!(boss(&mut app).unwrap().phase(), BossPhase::Minions);
+        assert_eq!(enemies(&mut app), 3);
+        assert_eq!(enemy_count(&mut app), 4);
+    }
+
+    #[test]
+    fn test_boss_defeat() {
+        let mut app = headless_app();
+        summon_boss(&mut app);
+
+        let position = {
+            let (boss, mut health) = app
+                .world
+                .query::<(&Boss, &mut Health)>()
+                .single_mut(&mut app.world);
+            health.current = 1;
+            boss.position
+        };
+        let points = app.world.resource::<Score>().points;
+        shoot(&mut app, position);
+        app.update();
+        app.update();
+
+        let health = app
+            .world
+            .query_filtered::<&Health, With<Boss>>()
+            .single(&app.world);
+        assert!(!health.is_alive());
+        assert_eq!(enemy_count(&mut app), 0);
+        assert!(app.world.resource::<Score>().points >= points + 5000);
+
+        for _ in 0..(3.0 / TIMESTEP) as u32 {
+            app.update();
+        }
+
+        assert!(boss(&mut app).is_none());
+        assert_eq!(app.world.query::<&Wave>().single(&app.world).number, 2);
+        assert_eq!(
+            app.world.resource::<State<AppState>>().current(),
+            &AppState::Main
+        );
+    }
+
+    #[test]
+    fn test_boss_death_blasts() {
+        let mut app = headless_app();
+        summon_boss(&mut app);
+
+        let position = {
+            let (boss, mut health) = app
+                .world
+                .query::<(&Boss, &mut Health)>()
+                .single_mut(&mut app.world);
+            health.current = 1;
+            boss.position
+        };
+        shoot(&mut app, position);
+        app.update();
+        app.update();
+
+        // Nothing blows up on the killing blow, the blast chain starts a moment later.
+        let events = app.world.resource::<Events<AddExplosionEvent>>();
+        assert_eq!(events.get_reader().iter(events).count(), 0);
+
+        let mut explosions = 0;
+        for _ in 0..(3.0 / TIMESTEP) as u32 {
+            app.update();
+            let events = app.world.resource::<Events<AddExplosionEvent>>();
+            explosions += events.iter_current_update_events().count();
+        }
+        assert!(boss(&mut app).is_none());
+        assert!(explosions > 1);
+    }
+
+    #[test]
+    fn test_boss_victory() {
+        let mut app = headless_app();
+        app.world.resource_mut::<GameConfig>().waves.final_wave = 1;
+        summon_boss(&mut app);
+
+        assert_eq!(
+            app.world.resource::<State<AppState>>().current(),
+            &AppState::Main
+        );
+
+        app.world
+            .query_filtered::<&mut Health, With<Boss>>()
+            .single_mut(&mut app.world)
+            .current = 1;
+        let position = boss(&mut app).unwrap().position;
+        shoot(&mut app, position);
+        for _ in 0..(2.0 / TIMESTEP) as u32 {
+            app.update();
+        }
+
+        assert_eq!(
+            app.world.resource::<State<AppState>>().current(),
+            &AppState::Victory
+        );
+    }
+
+    #[test]
+    fn test_game_over_and_play_again() {
+        let mut app = headless_app();
+
+        for mut character_lifes in app
+            .world
+            .query::<&mut CharacterLifes>()
+            .iter_mut(&mut app.world)
+        {
+            character_lifes.lifes = 1;
+        }
+        for mut enemy in app.world.query::<&mut Enemy>().iter_mut(&mut app.world) {
+            enemy.position = Vec2::ZERO;
+        }
+        for _ in 0..(2.0 / TIMESTEP) as u32 {
+            app.update();
+        }
+
+        assert_eq!(
+            app.world.resource::<State<AppState>>().current(),
+            &AppState::GameOver
+        );
+        assert_eq!(enemies(&mut app), 0);
+        assert_eq!(app.world.query::<&Character>().iter(&app.world).count(), 0);
+
+        for mut interaction in app
+            .world
+            .query_filtered::<&mut Interaction, With<PlayAgainButton>>()
+            .iter_mut(&mut app.world)
+        {
+            *interaction = Interaction::Clicked;
+        }
+        app.update();
+        app.update();
+
+        assert_eq!(
+            app.world.resource::<State<AppState>>().current(),
+            &AppState::Main
+        );
+        assert_eq!(enemies(&mut app), 5);
+        assert_eq!(enemy_count(&mut app), 5);
+        assert_eq!(character_lifes(&mut app), 3);
+        assert_eq!(app.world.query::<&Character>().iter(&app.world).count(), 1);
+        assert_eq!(app.world.query::<&Wave>().single(&app.world).number, 1);
+    }
+
+    #[test]
+    fn test_high_score_entry() {
+        let mut app = headless_app();
+
+        app.world.resource_mut::<Score>().points = 500;
+        for mut character_lifes in app
+            .world
+            .query::<&mut CharacterLifes>()
+            .iter_mut(&mut app.world)
+        {
+            character_lifes.lifes = 1;
+        }
+        for mut enemy in app.world.query::<&mut Enemy>().iter_mut(&mut app.world) {
+            enemy.position = Vec2::ZERO;
+        }
+        for _ in 0..(2.0 / TIMESTEP) as u32 {
+            app.update();
+        }
+
+        assert_eq!(
+            app.world.resource::<State<AppState>>().current(),
+            &AppState::GameOver
+        );
+        assert_eq!(app.world.query::<&NameEntry>().iter(&app.world).count(), 1);
+
+        for char in "Ace!".chars() {
+            app.world.send_event(ReceivedCharacter {
+                id: WindowId::primary(),
+                char,
+            });
+        }
+        app.update();
+        tap_key(&mut app, KeyCode::Return);
+
+        assert_eq!(app.world.query::<&NameEntry>().iter(&app.world).count(), 0);
+        let high_scores = app.world.resource::<HighScores>();
+        assert_eq!(high_scores.entries.len(), 1);
+        assert_eq!(high_scores.entries[0].name, "Ace");
+        assert_eq!(high_scores.entries[0].score, 500);
+        assert_eq!(high_scores.entries[0].wave, 1);
+    }
+
+    #[test]
+    fn test_pause() {
+        let mut app = headless_app();
+
+        tap_key(&mut app, KeyCode::Escape);
+        assert_eq!(
+            app.world.resource::<State<AppState>>().current(),
+            &AppState::Paused
+        );
+
+        let positions = line_up_enemies(&mut app);
+        for mut enemy in app.world.query::<&mut Enemy>().iter_mut(&mut app.world) {
+            enemy.speed = Vec2::ONE * 100.0;
+        }
+        for _ in 0..10 {
+            app.update();
+        }
+        let paused_positions: Vec<Vec2> = app
+            .world
+            .query::<&Enemy>()
+            .iter(&app.world)
+            .map(|enemy| enemy.position)
+            .collect();
+        assert_eq!(paused_positions, positions);
+
+        tap_key(&mut app, KeyCode::Escape);
+        assert_eq!(
+            app.world.resource::<State<AppState>>().current(),
+            &AppState::Main
+        );
+        assert_eq!(enemies(&mut app), 5);
+    }
+
+    fn click_pause_button(app: &mut App, pause_button: PauseButton) {
+        tap_key(app, KeyCode::Escape);
+        assert_eq!(
+            app.world.resource::<State<AppState>>().current(),
+            &AppState::Paused
+        );
+
+        for (mut interaction, button) in app
+            .world
+            .query::<(&mut Interaction, &PauseButton)>()
+            .iter_mut(&mut app.world)
+        {
+            if *button == pause_button {
+                *interaction = Interaction::Clicked;
+            }
+        }
+        app.update();
+        app.update();
+    }
+
+    #[test]
+    fn test_pause_restart() {
+        let mut app = headless_app();
+        let character = app
+            .world
+            .query_filtered::<Entity, With<Character>>()
+            .single(&app.world);
+        line_up_enemies(&mut app);
+        app.world.resource_mut::<Score>().points = 500;
+        tap_key(&mut app, KeyCode::Space);
+
+        click_pause_button(&mut app, PauseButton::Restart);
+
+        assert_eq!(
+            app.world.resource::<State<AppState>>().current(),
+            &AppState::Main
+        );
+        let restarted = app
+            .world
+            .query_filtered::<Entity, With<Character>>()
+            .single(&app.world);
+        assert_ne!(restarted, character);
+        assert_eq!(enemies(&mut app), 5);
+        assert_eq!(enemy_count(&mut app), 5);
+        assert_eq!(bomb_charges(&mut app), 3);
+        assert_eq!(app.world.resource::<Score>().points, 0);
+        assert_eq!(
+            app.world.query::<&PauseButton>().iter(&app.world).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_pause_quit_to_menu() {
+        let mut app = headless_app();
+
+        click_pause_button(&mut app, PauseButton::Menu);
+
+        assert_eq!(
+            app.world.resource::<State<AppState>>().current(),
+            &AppState::Menu
+        );
+        assert_eq!(enemies(&mut app), 0);
+        assert_eq!(app.world.query::<&Character>().iter(&app.world).count(), 0);
+        assert_eq!(
+            app.world.query::<&PauseButton>().iter(&app.world).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_pause_resume_and_click() {
+        let mut app = headless_app();
+        tap_key(&mut app, KeyCode::Escape);
+
+        for (mut interaction, button) in app
+            .world
+            .query::<(&mut Interaction, &PauseButton)>()
+            .iter_mut(&mut app.world)
+        {
+            if *button == PauseButton::Menu {
+                *interaction = Interaction::Clicked;
+            }
+        }
+        tap_key(&mut app, KeyCode::Escape);
+        app.update();
+
+        assert_eq!(
+            app.world.resource::<State<AppState>>().current(),
+            &AppState::Main
+        );
+    }
+
+    fn connect_gamepad(app: &mut App) {
+        app.world.send_event(GamepadEventRaw::new(
+            Gamepad::new(0),
+            GamepadEventType::Connected(GamepadInfo {
+                name: "Test pad".to_string(),
+            }),
+        ));
+        app.update();
+    }
+
+    fn move_stick(app: &mut App, x: GamepadAxisType, y: GamepadAxisType, value: Vec2) {
+        for (axis_type, value) in [(x, value.x), (y, value.y)] {
+            app.world.send_event(GamepadEventRaw::new(
+                Gamepad::new(0),
+                GamepadEventType::AxisChanged(axis_type, value),
+            ));
+        }
+        app.update();
+    }
+
+    fn tap_button(app: &mut App, button_type: GamepadButtonType) {
+        for value in [1.0, 0.0] {
+            app.world.send_event(GamepadEventRaw::new(
+                Gamepad::new(0),
+                GamepadEventType::ButtonChanged(button_type, value),
+            ));
+            app.update();
+        }
+    }
+
+    #[test]
+    fn test_gamepad_move() {
+        let mut app = headless_app();
+        line_up_enemies(&mut app);
+        connect_gamepad(&mut app);
+        let max_speed = app.world.resource::<GameConfig>().character.max_speed;
+
+        // Drift inside the dead zone leaves the character where it is.
+        move_stick(
+            &mut app,
+            GamepadAxisType::LeftStickX,
+            GamepadAxisType::LeftStickY,
+            Vec2::new(0.1, 0.0),
+        );
+        for _ in 0..30 {
+            app.update();
+        }
+        assert_eq!(character_position(&mut app), Vec2::ZERO);
+
+        move_stick(
+            &mut app,
+            GamepadAxisType::LeftStickX,
+            GamepadAxisType::LeftStickY,
+            Vec2::new(0.5, 0.0),
+        );
+        let start = character_position(&mut app);
+        for _ in 0..30 {
+            app.update();
+        }
+        let distance = character_position(&mut app).x - start.x;
+        assert!((distance - 0.5 * max_speed * 30.0 * TIMESTEP as f32).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_gamepad_aim_and_fire() {
+        let mut app = headless_app();
+        line_up_enemies(&mut app);
+        connect_gamepad(&mut app);
+
+        move_stick(
+            &mut app,
+            GamepadAxisType::RightStickX,
+            GamepadAxisType::RightStickY,
+            Vec2::new(0.0, -0.8),
+        );
+        app.update();
+
+        let rotation = app
+            .world
+            .query_filtered::<&Transform, With<Character>>()
+            .single(&app.world)
+            .rotation;
+        assert!(rotation.angle_between(Quat::from_rotation_z(PI)) < 0.01);
+        let aim = app
+            .world
+            .query_filtered::<&Transform, With<Aim>>()
+            .single(&app.world)
+            .translation;
+        assert!(aim.x.abs() < 0.01 && aim.y < 0.0);
+
+        // Lets the initial weapon delay run out.
+        for _ in 0..(0.5 / TIMESTEP) as u32 {
+            app.update();
+        }
+        let shots = app.world.resource::<Stats>().shots;
+        tap_button(&mut app, GamepadButtonType::RightTrigger2);
+        assert_eq!(app.world.resource::<Stats>().shots, shots + 1);
+
+        let speed = app.world.query::<&Bullet>().single(&app.world).speed();
+        assert!(speed.x.abs() < 0.01 && speed.y < 0.0);
+    }
+
+    /// Sends a touch at `position`, given in world coordinates.
+    fn touch(app: &mut App, id: u64, phase: TouchPhase, position: Vec2) {
+        app.world.send_event(TouchInput {
+            phase,
+            position: Vec2::new(
+                position.x + WINDOW_SIZE.width / 2.0,
+                WINDOW_SIZE.height / 2.0 - position.y,
+            ),
+            force: None,
+            id,
+        });
+        app.update();
+    }
+
+    #[test]
+    fn test_touch_controls() {
+        let mut app = headless_app();
+        line_up_enemies(&mut app);
+        assert!(!app.world.resource::<TouchControls>().enabled);
+        let max_speed = app.world.resource::<GameConfig>().character.max_speed;
+
+        touch(&mut app, 1, TouchPhase::Started, MOVE_STICK_CENTER);
+        assert!(app.world.resource::<TouchControls>().enabled);
+        let moved = MOVE_STICK_CENTER + Vec2::new(0.5 * STICK_RADIUS, 0.0);
+        touch(&mut app, 1, TouchPhase::Moved, moved);
+        let start = character_position(&mut app);
+        for _ in 0..30 {
+            app.update();
+        }
+        let distance = character_position(&mut app).x - start.x;
+        assert!((distance - 0.5 * max_speed * 30.0 * TIMESTEP as f32).abs() < 0.01);
+        touch(&mut app, 1, TouchPhase::Ended, moved);
+
+        touch(&mut app, 2, TouchPhase::Started, AIM_STICK_CENTER);
+        let pushed = AIM_STICK_CENTER - Vec2::new(0.0, STICK_RADIUS);
+        touch(&mut app, 2, TouchPhase::Moved, pushed);
+        app.update();
+        let rotation = app
+            .world
+            .query_filtered::<&Transform, With<Character>>()
+            .single(&app.world)
+            .rotation;
+        assert!(rotation.angle_between(Quat::from_rotation_z(PI)) < 0.01);
+
+        for _ in 0..(0.5 / TIMESTEP) as u32 {
+            app.update();
+        }
+        let shots = app.world.resource::<Stats>().shots;
+        touch(&mut app, 3, TouchPhase::Started, FIRE_BUTTON_CENTER);
+        touch(&mut app, 3, TouchPhase::Ended, FIRE_BUTTON_CENTER);
+        assert_eq!(app.world.resource::<Stats>().shots, shots + 1);
+
+        let speed = app.world.query::<&Bullet>().single(&app.world).speed();
+        assert!(speed.angle_between(Vec2::NEG_Y).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_gamepad_menu() {
+        let mut app = headless_app();
+        connect_gamepad(&mut app);
+        let character = app
+            .world
+            .query_filtered::<Entity, With<Character>>()
+            .single(&app.world);
+
+        tap_button(&mut app, GamepadButtonType::Start);
+        assert_eq!(
+            app.world.resource::<State<AppState>>().current(),
+            &AppState::Paused
+        );
+
+        // The first press focuses "Resume", the second one "Restart".
+        tap_button(&mut app, GamepadButtonType::DPadDown);
+        tap_button(&mut app, GamepadButtonType::DPadDown);
+        tap_button(&mut app, GamepadButtonType::South);
+
+        assert_eq!(
+            app.world.resource::<State<AppState>>().current(),
+            &AppState::Main
+        );
+        let restarted = app
+            .world
+            .query_filtered::<Entity, With<Character>>()
+            .single(&app.world);
+        assert_ne!(restarted, character);
+    }
+
+    /// Where everything is, to tell whether two runs went the same way.
+    fn run_state(app: &mut App) -> (Vec2, Vec<Vec2>, u32, WeaponKind) {
+        let enemies = app
+            .world
+            .query::<&Enemy>()
+            .iter(&app.world)
+            .map(|enemy| enemy.position)
+            .collect();
+        let shots = app.world.resource::<Stats>().shots;
+
+        (character_position(app), enemies, shots, weapon(app))
+    }
+
+    #[test]
+    fn test_replay() {
+        let mut app = seeded_app(Some(7));
+        app.world.send_event(KeyboardInput {
+            scan_code: 0,
+            key_code: Some(KeyCode::D),
+            state: ButtonState::Pressed,
+        });
+        for _ in 0..20 {
+            app.update();
+        }
+        app.world.send_event(CursorMoved {
+            id: WindowId::primary(),
+            position: Vec2::new(100.0, 400.0),
+        });
+        scroll(&mut app, 1.0);
+        tap_key(&mut app, KeyCode::D);
+        hold_fire(&mut app, 1.0);
+        for _ in 0..100 {
+            app.update();
+        }
+
+        let replay = app.world.resource::<Recorder>().replay().unwrap().clone();
+        let recorded = run_state(&mut app);
+        assert!(recorded.0.x > 0.0);
+        assert!(recorded.2 > 0);
+        assert_eq!(recorded.3, WeaponKind::Spread);
+        assert_eq!(
+            Replay::from_ron(&ron::to_string(&replay).unwrap()).unwrap(),
+            replay
+        );
+        assert!(!replay.incomplete);
+        assert!(matches!(
+            Replay::from_ron("(version: 0, seed: 7, frames: [])"),
+            Err(ReplayError::Version(0))
+        ));
+        assert!(matches!(
+            Replay::from_ron("(seed: 7)"),
+            Err(ReplayError::Parse(_))
+        ));
+
+        // Another seed and no input, everything comes from the replay. The run starts
+        // in the first startup update, so those played the first frames already.
+        let mut app = replay_app(Some(8), Some(replay.clone()));
+        for _ in STARTUP_UPDATES..replay.frames.len() {
+            app.update();
+        }
+        assert_eq!(run_state(&mut app), recorded);
+    }
+
+    #[test]
+    fn test_replay_keymap() {
+        let mut keymap = Keymap::default();
+        keymap.bind(Action::Fire, Binding::Key(KeyCode::F));
+        let storage = Storage::memory();
+        storage.save(stars_rs::controls::STORAGE_NAME, &keymap);
+        let mut app = storage_app(storage);
+        for _ in 0..10 {
+            app.update();
+        }
+        assert_eq!(hold(&mut app, Binding::Key(KeyCode::F), 1.0), 1);
+
+        let replay = app.world.resource::<Recorder>().replay().unwrap().clone();
+        assert_eq!(replay.keymap, keymap);
+
+        // The player of the replay kept the default keymap, F only fires with the
+        // replay's.
+        let mut app = replay_app(None, Some(replay.clone()));
+        for _ in STARTUP_UPDATES..replay.frames.len() {
+            app.update();
+        }
+        assert_eq!(app.world.resource::<Stats>().shots, 1);
+        assert_eq!(*app.world.resource::<Keymap>(), Keymap::default());
+    }
+
+    #[test]
+    fn test_replay_incomplete() {
+        let mut app = headless_app();
+        connect_gamepad(&mut app);
+        let replay = app.world.resource::<Recorder>().replay().unwrap();
+        assert!(!replay.incomplete);
+
+        tap_button(&mut app, GamepadButtonType::RightTrigger);
+        let replay = app.world.resource::<Recorder>().replay().unwrap();
+        assert!(replay.incomplete);
+    }
+}
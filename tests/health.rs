@@ -0,0 +1,40 @@
+extern crate stars_rs;
+
+#[cfg(test)]
+mod health {
+    use stars_rs::{
+        config::GameConfig,
+        enemies::EnemyKind,
+        health::{Damage, Health},
+        weapon::WeaponKind,
+    };
+
+    #[test]
+    fn test_take_damage() {
+        let mut health = Health::new(30);
+
+        assert!(!health.take(Damage(10)));
+        assert_eq!(health.current, 20);
+        assert!((health.fraction() - 2.0 / 3.0).abs() < f32::EPSILON);
+
+        assert!(health.take(Damage(25)));
+        assert_eq!(health.current, 0);
+        assert!(!health.is_alive());
+
+        assert!(!health.take(Damage(10)));
+    }
+
+    #[test]
+    fn test_tank_takes_three_shots() {
+        let config = GameConfig::default();
+        let damage = WeaponKind::Single.damage(&config.bullet);
+        let mut health = Health::new(EnemyKind::Tank.health(&config.enemies));
+
+        let shots = (0..10).take_while(|_| !health.take(damage)).count() + 1;
+        assert_eq!(shots, 3);
+        assert_eq!(
+            WeaponKind::Laser.damage(&config.bullet),
+            Damage(2 * damage.0)
+        );
+    }
+}
@@ -0,0 +1,80 @@
+extern crate stars_rs;
+
+#[cfg(test)]
+mod high_scores {
+    use stars_rs::{
+        high_scores::{format_date, HighScoreEntry, HighScores, MAX_HIGH_SCORES, STORAGE_NAME},
+        storage::Storage,
+    };
+
+    fn entry(name: &str, score: u32) -> HighScoreEntry {
+        HighScoreEntry {
+            name: name.to_string(),
+            score,
+            wave: 1,
+            date: "2023-01-01".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_insert_keeps_order() {
+        let mut high_scores = HighScores::default();
+
+        assert_eq!(high_scores.insert(entry("a", 200)), Some(0));
+        assert_eq!(high_scores.insert(entry("b", 300)), Some(0));
+        assert_eq!(high_scores.insert(entry("c", 200)), Some(2));
+        assert_eq!(high_scores.insert(entry("d", 0)), None);
+
+        let names: Vec<&str> = high_scores
+            .entries
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect();
+        assert_eq!(names, ["b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_insert_keeps_top_entries() {
+        let mut high_scores = HighScores::default();
+        for score in 1..=MAX_HIGH_SCORES as u32 {
+            high_scores.insert(entry("a", score * 100));
+        }
+
+        assert!(!high_scores.qualifies(100));
+        assert!(high_scores.qualifies(101));
+        assert_eq!(high_scores.insert(entry("b", 550)), Some(5));
+        assert_eq!(high_scores.entries.len(), MAX_HIGH_SCORES);
+        assert_eq!(high_scores.entries.last().unwrap().score, 200);
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_672_531_199), "2022-12-31");
+    }
+
+    #[test]
+    fn test_file_storage() {
+        let dir = std::env::temp_dir().join(format!("stars_rs_test_{}", std::process::id()));
+        let storage = Storage::File(dir.clone());
+
+        assert_eq!(
+            storage.load::<HighScores>(STORAGE_NAME),
+            HighScores::default()
+        );
+
+        let mut high_scores = HighScores::default();
+        high_scores.insert(entry("a", 100));
+        storage.save(STORAGE_NAME, &high_scores);
+        assert_eq!(storage.load::<HighScores>(STORAGE_NAME), high_scores);
+
+        std::fs::write(dir.join("high_scores.ron"), "broken").unwrap();
+        assert_eq!(
+            storage.load::<HighScores>(STORAGE_NAME),
+            HighScores::default()
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,67 @@
+extern crate stars_rs;
+
+#[cfg(test)]
+mod spatial_hash {
+    use bevy::prelude::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use stars_rs::{
+        spatial_hash::SpatialHash,
+        utils::{random_in_range, Collider},
+    };
+    use std::f32::consts::PI;
+
+    struct Item;
+
+    fn circle(x: f32) -> Collider {
+        Collider::Circle {
+            center: Vec2::new(x, 0.0),
+            radius: 5.0,
+        }
+    }
+
+    #[test]
+    fn test_finds_every_hit() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut random_box = || Collider::OrientedBox {
+            center: Vec2::new(
+                random_in_range(&mut rng, -500.0, 500.0),
+                random_in_range(&mut rng, -500.0, 500.0),
+            ),
+            half_size: Vec2::new(
+                random_in_range(&mut rng, 1.0, 80.0),
+                random_in_range(&mut rng, 1.0, 80.0),
+            ),
+            rotation: random_in_range(&mut rng, -PI, PI),
+        };
+        let items: Vec<Collider> = (0..300).map(|_| random_box()).collect();
+        let probes: Vec<Collider> = (0..300).map(|_| random_box()).collect();
+
+        let mut grid = SpatialHash::<Item>::new(64.0);
+        for (index, item) in items.iter().enumerate() {
+            grid.insert(Entity::from_raw(index as u32), item);
+        }
+
+        for probe in probes {
+            let hits = |candidates: Vec<Entity>| -> Vec<Entity> {
+                candidates
+                    .into_iter()
+                    .filter(|entity| probe.intersects(&items[entity.index() as usize]))
+                    .collect()
+            };
+            let everything = (0..items.len() as u32).map(Entity::from_raw).collect();
+
+            assert_eq!(hits(grid.query(&probe)), hits(everything));
+        }
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut grid = SpatialHash::<Item>::new(64.0);
+        grid.insert(Entity::from_raw(0), &circle(0.0));
+        grid.clear();
+        grid.insert(Entity::from_raw(1), &circle(200.0));
+
+        assert!(grid.query(&circle(0.0)).is_empty());
+        assert_eq!(grid.query(&circle(200.0)), vec![Entity::from_raw(1)]);
+    }
+}
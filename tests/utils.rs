@@ -5,9 +5,43 @@ extern crate stars_rs;
 
 #[cfg(test)]
 mod utils {
-    use stars_rs::utils::{hit_test, random_in_range, random_in_rect_edge, BoundingRect};
+    use bevy::math::Vec2;
+    use rand::{rngs::StdRng, SeedableRng};
+    use stars_rs::{
+        bullet::Bullet,
+        enemy_bullet::EnemyBullet,
+        utils::{
+            hit_test, random_in_range, random_in_rect_edge, steer, BoundingRect, Collider,
+            GetBoundingRect,
+        },
+    };
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    fn rect(x: f32, y: f32, half_width: f32, half_height: f32, rotation: f32) -> Collider {
+        Collider::OrientedBox {
+            center: Vec2::new(x, y),
+            half_size: Vec2::new(half_width, half_height),
+            rotation,
+        }
+    }
+
+    fn circle(x: f32, y: f32, radius: f32) -> Collider {
+        Collider::Circle {
+            center: Vec2::new(x, y),
+            radius,
+        }
+    }
+
+    fn capsule(start: (f32, f32), end: (f32, f32), radius: f32) -> Collider {
+        Collider::Capsule {
+            start: Vec2::new(start.0, start.1),
+            end: Vec2::new(end.0, end.1),
+            radius,
+        }
+    }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_hit_test() {
         let lhr: BoundingRect = BoundingRect {
             x: 0.0,
@@ -38,10 +72,159 @@ mod utils {
         assert_eq!(hit_test(lhr, rhr), false);
     }
 
+    /// Offsets of a target along both axes, off the exact reach.
+    fn offsets() -> impl Iterator<Item = (f32, f32)> {
+        (0..120).flat_map(|step| {
+            let offset = step as f32 * 0.5 + 0.25;
+            [(offset, 0.0), (0.0, offset)]
+        })
+    }
+
+    fn target(x: f32, y: f32) -> BoundingRect {
+        BoundingRect {
+            x,
+            y,
+            width: 40.0,
+            height: 40.0,
+        }
+    }
+
+    #[test]
+    fn test_projectile_reach() {
+        let bullet = Bullet::new(Vec2::ZERO, Vec2::Y);
+        for (x, y) in offsets() {
+            assert_eq!(
+                bullet.get_collider().intersects(&target(x, y).into()),
+                hit_test(bullet.get_bounding_rect(), target(x, y)),
+                "target at {}, {}",
+                x,
+                y
+            );
+        }
+
+        // The diamond reaches further along the axes than the rect, by its corners.
+        let enemy_bullet = EnemyBullet::new(Vec2::ZERO, Vec2::Y);
+        for (x, y) in offsets() {
+            assert_eq!(
+                enemy_bullet.get_collider().intersects(&target(x, y).into()),
+                x.max(y) < 40.0 / 4.0 + 10.0 / 4.0 * 2.0_f32.sqrt(),
+                "target at {}, {}",
+                x,
+                y
+            );
+        }
+    }
+
+    #[test]
+    fn test_collider_from_bounding_rect() {
+        let lhr: Collider = BoundingRect {
+            x: 0.0,
+            y: 0.0,
+            width: 1.0,
+            height: 1.0,
+        }
+        .into();
+        let rhr = |offset| -> Collider {
+            BoundingRect {
+                x: offset,
+                y: offset,
+                width: 1.0,
+                height: 1.0,
+            }
+            .into()
+        };
+        assert!(lhr.intersects(&rhr(0.49)));
+        assert!(!lhr.intersects(&rhr(0.5)));
+    }
+
+    #[test]
+    fn test_rotated_rects() {
+        let subject = rect(0.0, 0.0, 1.0, 1.0, 0.0);
+        assert!(!subject.intersects(&rect(2.2, 0.0, 1.0, 1.0, 0.0)));
+        assert!(subject.intersects(&rect(2.2, 0.0, 1.0, 1.0, FRAC_PI_4)));
+
+        let needle = rect(0.0, 0.0, 3.0, 0.2, FRAC_PI_2);
+        assert!(!needle.intersects(&rect(2.0, 0.0, 1.0, 1.0, 0.0)));
+        assert!(needle.intersects(&rect(0.0, 3.5, 1.0, 1.0, 0.0)));
+        assert!(needle.intersects(&rect(0.0, 0.0, 3.0, 0.2, 0.0)));
+    }
+
+    #[test]
+    fn test_circles() {
+        assert!(circle(0.0, 0.0, 1.0).intersects(&circle(1.5, 0.0, 1.0)));
+        assert!(!circle(0.0, 0.0, 1.0).intersects(&circle(2.0, 0.0, 1.0)));
+
+        let subject = rect(0.0, 0.0, 1.0, 1.0, 0.0);
+        assert!(!subject.intersects(&circle(1.6, 1.6, 0.8)));
+        assert!(subject.intersects(&circle(1.6, 1.6, 0.9)));
+        assert!(subject.intersects(&circle(1.5, 0.0, 0.6)));
+        assert!(subject.intersects(&circle(0.2, 0.2, 0.1)));
+
+        let diamond = rect(0.0, 0.0, 1.0, 1.0, FRAC_PI_4);
+        assert!(!diamond.intersects(&circle(1.2, 1.2, 0.2)));
+        assert!(diamond.intersects(&circle(1.5, 0.0, 0.2)));
+    }
+
+    #[test]
+    fn test_capsules() {
+        let subject = capsule((-5.0, 3.0), (5.0, 3.0), 0.5);
+        assert!(!subject.intersects(&rect(0.0, 0.0, 1.0, 1.0, FRAC_PI_4)));
+        assert!(subject.intersects(&rect(0.0, 0.0, 3.0, 0.25, FRAC_PI_2)));
+        assert!(subject.intersects(&circle(5.5, 3.5, 0.5)));
+        assert!(!subject.intersects(&circle(5.5, 4.0, 0.5)));
+
+        assert!(subject.intersects(&capsule((0.0, 0.0), (1.0, 6.0), 0.1)));
+        assert!(!subject.intersects(&capsule((-5.0, 1.0), (5.0, 1.0), 0.5)));
+    }
+
+    #[test]
+    fn test_sweep() {
+        let bullet = capsule((0.0, -2.0), (0.0, 2.0), 1.0);
+        let target = rect(0.0, 50.0, 5.0, 5.0, 0.0);
+
+        let share = bullet.sweep(Vec2::new(0.0, 100.0), &target).unwrap();
+        assert_lt!((share - 0.42).abs(), 1e-3);
+        assert!(!bullet.translated(Vec2::new(0.0, 100.0)).intersects(&target));
+
+        assert_eq!(bullet.sweep(Vec2::new(0.0, -100.0), &target), None);
+        assert_eq!(bullet.sweep(Vec2::new(100.0, 100.0), &target), None);
+        assert_eq!(bullet.sweep(Vec2::ZERO, &target), None);
+        assert_eq!(
+            bullet.sweep(Vec2::new(0.0, 100.0), &circle(0.0, 0.0, 1.0)),
+            Some(0.0)
+        );
+    }
+
+    #[test]
+    fn test_sweep_rotated() {
+        let bullet = circle(-50.0, 0.0, 1.0);
+        let needle = rect(0.0, 10.0, 20.0, 1.0, FRAC_PI_2);
+        let motion = Vec2::new(100.0, 0.0);
+
+        let share = bullet.sweep(motion, &needle).unwrap();
+        assert_lt!((share - 0.48).abs(), 1e-3);
+        assert_eq!(bullet.sweep(motion, &rect(0.0, 10.0, 20.0, 1.0, 0.0)), None);
+    }
+
+    #[test]
+    fn test_sweep_bounds() {
+        let (min, max) = circle(0.0, 0.0, 1.0).swept_bounds(Vec2::new(10.0, -5.0));
+        assert_eq!(min, Vec2::new(-1.0, -6.0));
+        assert_eq!(max, Vec2::new(11.0, 1.0));
+    }
+
+    #[test]
+    fn test_collider_bounds() {
+        let (min, max) = rect(0.0, 0.0, 1.0, 1.0, FRAC_PI_4).bounds();
+        assert_lt!((max.x - 2.0_f32.sqrt()).abs(), 1e-5);
+        assert_lt!((min.y + 2.0_f32.sqrt()).abs(), 1e-5);
+    }
+
     #[test]
     fn test_random_in_range() {
+        let mut rng = StdRng::seed_from_u64(0);
         for _ in 0..10 {
-            let subject = random_in_range(0.0, 1.0);
+            let subject = random_in_range(&mut rng, 0.0, 1.0);
             assert_ge!(subject, 0.0);
             assert_le!(subject, 1.0);
         }
@@ -49,12 +232,38 @@ mod utils {
 
     #[test]
     fn test_random_in_rect_edge() {
+        let mut rng = StdRng::seed_from_u64(0);
         for _ in 0..10 {
-            let subject = random_in_rect_edge(-1.0, 1.0, 1.0, -1.0);
+            let subject = random_in_rect_edge(&mut rng, -1.0, 1.0, 1.0, -1.0);
             assert_ge!(subject.x, -1.0);
             assert_le!(subject.x, 1.0);
             assert_ge!(subject.y, -1.0);
             assert_le!(subject.y, 1.0);
         }
     }
+
+    #[test]
+    fn test_random_is_seeded() {
+        let mut lhr = StdRng::seed_from_u64(42);
+        let mut rhr = StdRng::seed_from_u64(42);
+        for _ in 0..10 {
+            assert_eq!(
+                random_in_rect_edge(&mut lhr, -1.0, 1.0, 1.0, -1.0),
+                random_in_rect_edge(&mut rhr, -1.0, 1.0, 1.0, -1.0)
+            );
+        }
+    }
+
+    #[test]
+    fn test_steer() {
+        let speed = Vec2::new(0.0, 2.0);
+        let target = Vec2::new(10.0, 0.0);
+
+        let subject = steer(Vec2::ZERO, speed, target, 0.5);
+        assert_gt!(subject.x, 0.0);
+        assert_lt!((subject.length() - 2.0).abs(), 1e-5);
+
+        assert_eq!(steer(Vec2::ZERO, speed, target, 1.0), Vec2::new(2.0, 0.0));
+        assert_eq!(steer(Vec2::ZERO, speed, target, 0.0), speed);
+    }
 }